@@ -9,11 +9,14 @@ pub mod queue;
 pub mod board;
 
 pub mod conditions;
+pub mod conditions_editor;
+pub mod stats;
 
 pub mod game;
 
 pub mod play_interface;
 pub mod edit_interface;
+pub mod replay_interface;
 
 pub mod key_binding;
 pub mod button_binding;
@@ -7,6 +7,7 @@ use ztrix::position::Position;
 use ztrix::game::PieceType;
 use ztrix::game::Mino;
 use ztrix::game::Board;
+use ztrix::game::BoardMask;
 use ztrix::game::ActivePiece;
 use ztrix::game::MaybeActive;
 
@@ -26,17 +27,48 @@ pub struct Props {
 	pub board: Board,
 	#[prop_or_default]
 	pub piece: Option<MaybeActive>,
+	#[prop_or_default]
+	pub target: Option<BoardMask>,
+	#[prop_or_default]
+	pub hint: Option<ActivePiece>,
 
 	#[prop_or_default]
 	pub onmouse: Callback<BoardMouseEvent>
 }
 
+pub fn mino_color(mino: Mino) -> &'static str {
+	match mino {
+		Mino::Piece(PieceType::I) => "#06A",
+		Mino::Piece(PieceType::O) => "#870",
+		Mino::Piece(PieceType::S) => "#070",
+		Mino::Piece(PieceType::Z) => "#700",
+		Mino::Piece(PieceType::J) => "#01B",
+		Mino::Piece(PieceType::L) => "#730",
+		Mino::Piece(PieceType::T) => "#607",
+		Mino::Gray => "#666",
+	}
+}
+
+pub fn piece_color(piece_type: PieceType) -> &'static str {
+	match piece_type {
+		PieceType::I => "#29D",
+		PieceType::O => "#CA0",
+		PieceType::S => "#1A1",
+		PieceType::Z => "#C12",
+		PieceType::J => "#03D",
+		PieceType::L => "#C51",
+		PieceType::T => "#819",
+	}
+}
+
 #[function_component(BoardComponent)]
 pub fn board(props: &Props) -> Html {
 	let state_position = use_state(|| None);
 
 	let board = props.board.clone();
 	let piece = props.piece.clone();
+	let target = props.target.clone();
+	let hint = props.hint.clone();
 	let canvas = use_canvas(move |canvas, context| {
 		let width = canvas.offset_width() as f64;
 		let height = canvas.offset_height() as f64;
@@ -57,16 +89,7 @@ pub fn board(props: &Props) -> Html {
     			let pos = Position::new(x, y);
     			if let Some(mino) = board[pos] {
 					context.set_fill_style(&JsValue::from_str(
-						match mino {
-							Mino::Piece(PieceType::I) => "#06A",
-							Mino::Piece(PieceType::O) => "#870",
-							Mino::Piece(PieceType::S) => "#070",
-							Mino::Piece(PieceType::Z) => "#700",
-							Mino::Piece(PieceType::J) => "#01B",
-							Mino::Piece(PieceType::L) => "#730",
-							Mino::Piece(PieceType::T) => "#607",
-							Mino::Gray => "#666",
-						}));
+						mino_color(mino)));
 					context.fill_rect(
 						block_size * x as f64,
 						height - block_size * (y + 1) as f64,
@@ -74,18 +97,26 @@ pub fn board(props: &Props) -> Html {
     			}
     		}
     	}
+		// draw the target cells
+		if let Some(target) = &target {
+			context.set_global_alpha(1.0);
+			context.set_stroke_style(&JsValue::from_str("#CA0"));
+			context.set_line_width(block_size / 10.0);
+			for y in 0..26 {
+				for x in 0..10 {
+					if target[Position::new(x, y)] {
+						context.stroke_rect(
+							block_size * (x as f64 + 0.1),
+							height - block_size * (y as f64 + 0.9),
+							block_size * 0.8, block_size * 0.8);
+					}
+				}
+			}
+		}
 		// draw the active piece
 		if let Some(piece) = &piece {
 			context.set_fill_style(&JsValue::from_str(
-				match piece.get_type() {
-					PieceType::I => "#29D",
-					PieceType::O => "#CA0",
-					PieceType::S => "#1A1",
-					PieceType::Z => "#C12",
-					PieceType::J => "#03D",
-					PieceType::L => "#C51",
-					PieceType::T => "#819",
-				}));
+				piece_color(piece.get_type())));
 			match piece {
 				MaybeActive::Active(active) => {
 					context.set_global_alpha(1.0);
@@ -123,6 +154,21 @@ pub fn board(props: &Props) -> Html {
 				}
 			}	
 		}
+		// outline where the hint says to place
+		if let Some(hint) = &hint {
+			context.set_global_alpha(1.0);
+			context.set_stroke_style(&JsValue::from_str(
+				piece_color(hint.get_type())));
+			context.set_line_width(block_size / 8.0);
+			for pos in hint.get_mino_positions() {
+				let x = pos.x as f64;
+				let y = pos.y as f64;
+				context.stroke_rect(
+					block_size * (x + 0.0625),
+					height - block_size * (y + 0.9375),
+					block_size * 0.875, block_size * 0.875);
+			}
+		}
 	});
 
 	let node_ref = canvas.clone();
@@ -169,9 +215,13 @@ pub fn board(props: &Props) -> Html {
 		} else {
 			position.set(None);
 		});
+	let onmouse = props.onmouse.clone();
 	let position = state_position.clone();
 	let onmouseup = Callback::from(move |e: MouseEvent|
 		if e.button() == 0 {
+			if position.is_some() {
+				onmouse.emit(BoardMouseEvent::Release);
+			}
 			position.set(None);
 		});
 	let onmouse = props.onmouse.clone();
@@ -207,9 +257,13 @@ pub fn board(props: &Props) -> Html {
 			}
 		}
 	});
+	let onmouse = props.onmouse.clone();
 	let position = state_position.clone();
 	let ontouchend = Callback::from(move |e: TouchEvent| {
 		e.prevent_default();
+		if position.is_some() {
+			onmouse.emit(BoardMouseEvent::Release);
+		}
 		position.set(None);
 	});
 	html! {
@@ -0,0 +1,798 @@
+use web_sys::HtmlInputElement;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use ztrix::condition::all_clear::AllClearType;
+use ztrix::condition::attack::AttackTable;
+use ztrix::condition::board_state::BoardCondition;
+use ztrix::condition::board_state::BoardGoal;
+use ztrix::condition::chain::ChainConditions;
+use ztrix::condition::chain::ChainHandler;
+use ztrix::condition::chain::ChainScorer;
+use ztrix::condition::event::Conditions;
+use ztrix::condition::event::EventConditions;
+use ztrix::condition::event::EventScorer;
+use ztrix::condition::event::ReqOrMin;
+use ztrix::condition::event::ScoreTarget;
+use ztrix::condition::progress::LineClearReqs;
+use ztrix::condition::sequence::Sequence;
+use ztrix::condition::spin::SpinConditions;
+use ztrix::condition::spin::SpinHandler;
+use ztrix::condition::spin::SpinScorer;
+use ztrix::condition::spin::SpinType;
+use ztrix::game::BoardMask;
+use ztrix::game::PieceType;
+
+use component::button::ButtonComponent;
+use controller::input_handler::ButtonEvent;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub name: String,
+    pub conditions: Conditions,
+    #[prop_or_default]
+    pub onchange: Callback<Conditions>,
+}
+
+// Fields
+
+fn number_field(label: &str, value: usize, onchange: Callback<usize>) -> Html {
+    html! {
+        <label>
+            {label}
+            <input type="number" min="0"
+                value={value.to_string()}
+                onchange={onchange.reform(move |e: Event|
+                    e.target_unchecked_into::<HtmlInputElement>()
+                        .value().parse().unwrap_or(value))}/>
+        </label>
+    }
+}
+
+fn check_field(label: &str, value: bool, onchange: Callback<bool>) -> Html {
+    html! {
+        <label>
+            {label}
+            <input type="checkbox"
+                checked={value}
+                onchange={onchange.reform(|e: Event|
+                    e.target_unchecked_into::<HtmlInputElement>()
+                        .checked())}/>
+        </label>
+    }
+}
+
+fn select_field(label: &str, options: &[&str], selected: usize,
+        onchange: Callback<usize>) -> Html {
+    html! {
+        <label>
+            {label}
+            <select onchange={onchange.reform(|e: Event|
+                e.target_unchecked_into::<HtmlSelectElement>()
+                    .selected_index() as usize)}>
+                {for options.iter().enumerate().map(|(i, option)| html! {
+                    <option selected={i == selected}>{*option}</option>
+                })}
+            </select>
+        </label>
+    }
+}
+
+fn target_field(target: &ScoreTarget, onchange: Callback<ScoreTarget>) -> Html {
+    let score = target.score;
+    number_field("Target", target.target, onchange.reform(move |target|
+        ScoreTarget { score, target }))
+}
+
+fn req_or_min_field(label: &str, value: ReqOrMin,
+        onchange: Callback<ReqOrMin>) -> Html {
+    let (mode, count) = match value {
+        ReqOrMin::Req(n) => (0, n),
+        ReqOrMin::Min(n) => (1, n),
+    };
+    let onmode = onchange.reform(move |mode| match mode {
+        0 => ReqOrMin::Req(count),
+        _ => ReqOrMin::Min(count),
+    });
+    let oncount = onchange.reform(move |count| match mode {
+        0 => ReqOrMin::Req(count),
+        _ => ReqOrMin::Min(count),
+    });
+    html! { <>
+        {select_field(label, &["Exactly", "At Least"], mode, onmode)}
+        {number_field("", count, oncount)}
+    </> }
+}
+
+const PIECES: [PieceType; 7] = [
+    PieceType::I, PieceType::O, PieceType::S, PieceType::Z,
+    PieceType::J, PieceType::L, PieceType::T,
+];
+
+fn piece_field(value: Option<PieceType>,
+        onchange: Callback<Option<PieceType>>) -> Html {
+    let selected = value.map_or(0, |p| p as usize + 1);
+    select_field("Piece", &["Any", "I", "O", "S", "Z", "J", "L", "T"],
+        selected, onchange.reform(|i: usize| match i {
+            0 => None,
+            i => Some(PIECES[i - 1]),
+        }))
+}
+
+const ALL_CLEARS: [AllClearType; 4] = [
+    AllClearType::NONE, AllClearType::GRAY_CLEAR,
+    AllClearType::COLOR_CLEAR, AllClearType::ALL_CLEAR,
+];
+
+fn all_clear_field(value: &AllClearType,
+        onchange: Callback<AllClearType>) -> Html {
+    let selected = ALL_CLEARS.iter().position(|a| a == value).unwrap_or(0);
+    select_field("All Clear", &["Any", "Gray Clear", "Color Clear", "All Clear"],
+        selected, onchange.reform(|i: usize| ALL_CLEARS[i].clone()))
+}
+
+fn spin_field(value: Option<Option<SpinType>>,
+        onchange: Callback<Option<Option<SpinType>>>) -> Html {
+    let selected = match value {
+        None => 0,
+        Some(None) => 1,
+        Some(Some(SpinType::Mini)) => 2,
+        Some(Some(SpinType::Full)) => 3,
+    };
+    select_field("Spin", &["Any", "No Spin", "Mini", "Full"],
+        selected, onchange.reform(|i: usize| match i {
+            0 => None,
+            1 => Some(None),
+            2 => Some(Some(SpinType::Mini)),
+            _ => Some(Some(SpinType::Full)),
+        }))
+}
+
+fn option_bool_field(label: &str, value: Option<bool>,
+        onchange: Callback<Option<bool>>) -> Html {
+    let selected = match value {
+        None => 0,
+        Some(true) => 1,
+        Some(false) => 2,
+    };
+    select_field(label, &["Any", "Yes", "No"],
+        selected, onchange.reform(|i: usize| match i {
+            0 => None,
+            1 => Some(true),
+            _ => Some(false),
+        }))
+}
+
+fn on_release(callback: Callback<()>) -> Callback<ButtonEvent<()>> {
+    Callback::from(move |e: ButtonEvent<()>|
+        if let ButtonEvent::Release(_) = e {
+            callback.emit(());
+        })
+}
+
+fn render_list<T: Clone + 'static>(items: &[T], onchange: Callback<Vec<T>>,
+        render: fn(&T, Callback<T>) -> Html, new_item: fn() -> T) -> Html {
+    let onadd = {
+        let items = items.to_vec();
+        let onchange = onchange.clone();
+        on_release(Callback::from(move |_| {
+            let mut items = items.clone();
+            items.push(new_item());
+            onchange.emit(items);
+        }))
+    };
+    html! {
+        <div class="condition-list">
+            {for items.iter().enumerate().map(|(i, item)| {
+                let onitem = {
+                    let items = items.to_vec();
+                    onchange.reform(move |item| {
+                        let mut items = items.clone();
+                        items[i] = item;
+                        items
+                    })
+                };
+                let onremove = {
+                    let items = items.to_vec();
+                    let onchange = onchange.clone();
+                    on_release(Callback::from(move |_| {
+                        let mut items = items.clone();
+                        items.remove(i);
+                        onchange.emit(items);
+                    }))
+                };
+                html! {
+                    <div class="condition-item">
+                        {render(item, onitem)}
+                        <ButtonComponent onbutton={onremove}>
+                            <img src="/assets/remove.png" alt="Remove"/>
+                        </ButtonComponent>
+                    </div>
+                }
+            })}
+            <ButtonComponent onbutton={onadd}>
+                <img src="/assets/add.png" alt="Add"/>
+            </ButtonComponent>
+        </div>
+    }
+}
+
+// Editing the steps starts the sequence over
+fn render_sequence<S: Clone + 'static>(sequence: &Sequence<S>,
+        onchange: Callback<Sequence<S>>,
+        render_step: fn(&S, Callback<S>) -> Html, new_step: fn() -> S) -> Html {
+    let steps = sequence.steps.clone();
+    let onconsecutive = onchange.reform(move |consecutive|
+        Sequence::new(steps.clone(), consecutive));
+    let consecutive = sequence.consecutive;
+    let onsteps = onchange.reform(move |steps|
+        Sequence::new(steps, consecutive));
+    html! { <>
+        {check_field("Consecutive", sequence.consecutive, onconsecutive)}
+        {render_list(&sequence.steps, onsteps, render_step, new_step)}
+    </> }
+}
+
+// Line clears, showing only the requirements each level can check
+
+#[derive(PartialEq, PartialOrd)]
+enum Level {
+    Event,
+    Spin,
+    Chain,
+}
+
+fn new_line_clear() -> LineClearReqs {
+    LineClearReqs {
+        negate: false,
+        req_lines: ReqOrMin::Min(1),
+        req_piece: None,
+        req_all_clear: AllClearType::NONE,
+        req_spin: None,
+        req_hard: None,
+        req_b2b: None,
+        req_combo: ReqOrMin::Min(0),
+    }
+}
+
+fn render_line_clear(reqs: &LineClearReqs, level: Level,
+        onchange: Callback<LineClearReqs>) -> Html {
+    let r = reqs.clone();
+    let onlines = onchange.reform(move |req_lines|
+        LineClearReqs { req_lines, ..r.clone() });
+    let r = reqs.clone();
+    let onpiece = onchange.reform(move |req_piece|
+        LineClearReqs { req_piece, ..r.clone() });
+    let r = reqs.clone();
+    let onall_clear = onchange.reform(move |req_all_clear|
+        LineClearReqs { req_all_clear, ..r.clone() });
+    let r = reqs.clone();
+    let onspin = onchange.reform(move |req_spin|
+        LineClearReqs { req_spin, ..r.clone() });
+    let r = reqs.clone();
+    let onhard = onchange.reform(move |req_hard|
+        LineClearReqs { req_hard, ..r.clone() });
+    let r = reqs.clone();
+    let onb2b = onchange.reform(move |req_b2b|
+        LineClearReqs { req_b2b, ..r.clone() });
+    let r = reqs.clone();
+    let oncombo = onchange.reform(move |req_combo|
+        LineClearReqs { req_combo, ..r.clone() });
+    let r = reqs.clone();
+    let onnegate = onchange.reform(move |negate|
+        LineClearReqs { negate, ..r.clone() });
+    html! { <>
+        {req_or_min_field("Lines", reqs.req_lines, onlines)}
+        {piece_field(reqs.req_piece, onpiece)}
+        {all_clear_field(&reqs.req_all_clear, onall_clear)}
+        {if level >= Level::Spin {
+            html! { <>
+                {spin_field(reqs.req_spin.clone(), onspin)}
+                {option_bool_field("Hard", reqs.req_hard, onhard)}
+            </> }
+        } else {
+            html! {}
+        }}
+        {if level >= Level::Chain {
+            html! { <>
+                {option_bool_field("B2B", reqs.req_b2b, onb2b)}
+                {req_or_min_field("Combo", reqs.req_combo, oncombo)}
+            </> }
+        } else {
+            html! {}
+        }}
+        {check_field("Negate", reqs.negate, onnegate)}
+    </> }
+}
+
+// Chain conditions
+
+const CHAIN_SCORERS: [&str; 7] = [
+    "Line Clears", "Zone Clears", "Lines Cleared",
+    "Damage", "Jeapordy", "Attack Table", "Points",
+];
+
+const ATTACK_TABLES: [&str; 6] = [
+    "TETR.IO", "Puyo Puyo Tetris", "Jstris", "TEC", "TEC With Zones", "Jeapordy",
+];
+
+fn attack_table_of(index: usize) -> AttackTable {
+    match index {
+        0 => AttackTable::tetrio(),
+        1 => AttackTable::puyo_puyo_tetris(),
+        2 => AttackTable::jstris(),
+        3 => AttackTable::tec(false),
+        4 => AttackTable::tec(true),
+        _ => AttackTable::jeapordy(),
+    }
+}
+
+// Tables from links that match no preset stay selected as custom
+fn attack_table_field(table: &AttackTable,
+        onchange: Callback<AttackTable>) -> Html {
+    let preset = (0..ATTACK_TABLES.len())
+        .position(|i| attack_table_of(i) == *table);
+    let mut options = ATTACK_TABLES.to_vec();
+    if preset.is_none() {
+        options.push("Custom");
+    }
+    let custom = table.clone();
+    select_field("Table", &options, preset.unwrap_or(ATTACK_TABLES.len()),
+        onchange.reform(move |i| match i < ATTACK_TABLES.len() {
+            true => attack_table_of(i),
+            false => custom.clone(),
+        }))
+}
+
+fn chain_scorer_index(scorer: &ChainScorer) -> usize {
+    match scorer {
+        ChainScorer::LineClear {..} => 0,
+        ChainScorer::ZoneClear {..} => 1,
+        ChainScorer::LinesCleared => 2,
+        ChainScorer::DamageDealt {..} => 3,
+        ChainScorer::JeapordyDealt => 4,
+        ChainScorer::Attack(_) => 5,
+        ChainScorer::Points => 6,
+    }
+}
+
+fn chain_line_clear(reqs: LineClearReqs) -> ChainScorer {
+    ChainScorer::LineClear {
+        req_lines: reqs.req_lines,
+        req_piece: reqs.req_piece,
+        req_all_clear: reqs.req_all_clear,
+        req_spin: reqs.req_spin,
+        req_hard: reqs.req_hard,
+        req_b2b: reqs.req_b2b,
+        req_combo: reqs.req_combo,
+        negate: reqs.negate,
+    }
+}
+
+fn new_chain_scorer(index: usize) -> ChainScorer {
+    match index {
+        0 => chain_line_clear(new_line_clear()),
+        1 => ChainScorer::ZoneClear { req_lines: ReqOrMin::Min(1) },
+        2 => ChainScorer::LinesCleared,
+        3 => ChainScorer::DamageDealt { count_zone_damage: false },
+        4 => ChainScorer::JeapordyDealt,
+        5 => ChainScorer::Attack(AttackTable::tec(true)),
+        _ => ChainScorer::Points,
+    }
+}
+
+fn render_chain_scorer(scorer: &ChainScorer,
+        onchange: Callback<ChainScorer>) -> Html {
+    let fields = match scorer {
+        ChainScorer::LineClear { req_lines, req_piece, req_all_clear,
+                req_spin, req_hard, req_b2b, req_combo, negate } =>
+            render_line_clear(&LineClearReqs {
+                negate: *negate,
+                req_lines: *req_lines,
+                req_piece: *req_piece,
+                req_all_clear: req_all_clear.clone(),
+                req_spin: req_spin.clone(),
+                req_hard: *req_hard,
+                req_b2b: *req_b2b,
+                req_combo: *req_combo,
+            }, Level::Chain, onchange.reform(chain_line_clear)),
+        ChainScorer::ZoneClear { req_lines } =>
+            req_or_min_field("Lines", *req_lines, onchange.reform(
+                |req_lines| ChainScorer::ZoneClear { req_lines })),
+        ChainScorer::DamageDealt { count_zone_damage } =>
+            check_field("Count Zones", *count_zone_damage, onchange.reform(
+                |count_zone_damage| ChainScorer::DamageDealt { count_zone_damage })),
+        ChainScorer::Attack(table) =>
+            attack_table_field(table, onchange.reform(ChainScorer::Attack)),
+        _ => html! {},
+    };
+    html! { <>
+        {select_field("Count", &CHAIN_SCORERS, chain_scorer_index(scorer),
+            onchange.reform(new_chain_scorer))}
+        {fields}
+    </> }
+}
+
+const CHAIN_CONDITIONS: [&str; 2] = ["Condition", "Sequence"];
+
+fn new_chain_condition_of(index: usize) -> ChainConditions {
+    match index {
+        0 => ChainConditions::Condition(ScoreTarget { score: 0, target: 1 },
+            new_chain_scorer(0)),
+        _ => ChainConditions::Sequence(Sequence::new(Vec::new(), false)),
+    }
+}
+
+fn new_chain_condition() -> ChainConditions {
+    new_chain_condition_of(0)
+}
+
+fn render_chain_condition(condition: &ChainConditions,
+        onchange: Callback<ChainConditions>) -> Html {
+    let (index, fields) = match condition {
+        ChainConditions::Condition(target, scorer) => {
+            let s = scorer.clone();
+            let ontarget = onchange.reform(move |target|
+                ChainConditions::Condition(target, s.clone()));
+            let t = target.clone();
+            let onscorer = onchange.reform(move |scorer|
+                ChainConditions::Condition(t.clone(), scorer));
+            (0, html! { <>
+                {target_field(target, ontarget)}
+                {render_chain_scorer(scorer, onscorer)}
+            </> })
+        }
+        ChainConditions::Sequence(sequence) =>
+            (1, render_sequence(sequence,
+                onchange.reform(ChainConditions::Sequence),
+                render_chain_scorer, || new_chain_scorer(0))),
+    };
+    html! {
+        <div class="condition-editor">
+            {select_field("Type", &CHAIN_CONDITIONS, index,
+                onchange.reform(new_chain_condition_of))}
+            {fields}
+        </div>
+    }
+}
+
+// Spin conditions
+
+const SPIN_SCORERS: [&str; 3] = ["Line Clears", "Zone Clears", "Lines Cleared"];
+
+fn spin_line_clear(reqs: LineClearReqs) -> SpinScorer {
+    SpinScorer::LineClear {
+        req_lines: reqs.req_lines,
+        req_piece: reqs.req_piece,
+        req_all_clear: reqs.req_all_clear,
+        req_spin: reqs.req_spin,
+        req_hard: reqs.req_hard,
+        negate: reqs.negate,
+    }
+}
+
+fn new_spin_scorer(index: usize) -> SpinScorer {
+    match index {
+        0 => spin_line_clear(new_line_clear()),
+        1 => SpinScorer::ZoneClear { req_lines: ReqOrMin::Min(1) },
+        _ => SpinScorer::LinesCleared,
+    }
+}
+
+fn render_spin_scorer(scorer: &SpinScorer,
+        onchange: Callback<SpinScorer>) -> Html {
+    let (index, fields) = match scorer {
+        SpinScorer::LineClear { req_lines, req_piece, req_all_clear,
+                req_spin, req_hard, negate } =>
+            (0, render_line_clear(&LineClearReqs {
+                negate: *negate,
+                req_lines: *req_lines,
+                req_piece: *req_piece,
+                req_all_clear: req_all_clear.clone(),
+                req_spin: req_spin.clone(),
+                req_hard: *req_hard,
+                ..new_line_clear()
+            }, Level::Spin, onchange.reform(spin_line_clear))),
+        SpinScorer::ZoneClear { req_lines } =>
+            (1, req_or_min_field("Lines", *req_lines, onchange.reform(
+                |req_lines| SpinScorer::ZoneClear { req_lines }))),
+        SpinScorer::LinesCleared => (2, html! {}),
+    };
+    html! { <>
+        {select_field("Count", &SPIN_SCORERS, index,
+            onchange.reform(new_spin_scorer))}
+        {fields}
+    </> }
+}
+
+const SPIN_CONDITIONS: [&str; 4] = [
+    "Condition", "Chain", "Chain With Zone", "Sequence",
+];
+
+fn new_spin_condition_of(index: usize) -> SpinConditions {
+    match index {
+        0 => SpinConditions::Condition(ScoreTarget { score: 0, target: 1 },
+            new_spin_scorer(0)),
+        1 => SpinConditions::ChainContext(ChainHandler::new(false, 0), Vec::new()),
+        2 => SpinConditions::ZoneChainContext(ChainHandler::new(false, 0), Vec::new()),
+        _ => SpinConditions::Sequence(Sequence::new(Vec::new(), false)),
+    }
+}
+
+fn new_spin_condition() -> SpinConditions {
+    new_spin_condition_of(0)
+}
+
+fn render_chain_context(handler: &ChainHandler, conditions: &[ChainConditions],
+        onchange: Callback<(ChainHandler, Vec<ChainConditions>)>) -> Html {
+    let c = conditions.to_vec();
+    let combo = handler.combo;
+    let onb2b = onchange.reform(move |b2b|
+        (ChainHandler::new(b2b, combo), c.clone()));
+    let c = conditions.to_vec();
+    let b2b = handler.b2b;
+    let oncombo = onchange.reform(move |combo|
+        (ChainHandler::new(b2b, combo), c.clone()));
+    let h = handler.clone();
+    let onconditions = onchange.reform(move |conditions|
+        (h.clone(), conditions));
+    html! { <>
+        {check_field("Start With B2B", handler.b2b, onb2b)}
+        {number_field("Start Combo", handler.combo, oncombo)}
+        {render_list(conditions, onconditions,
+            render_chain_condition, new_chain_condition)}
+    </> }
+}
+
+fn render_spin_condition(condition: &SpinConditions,
+        onchange: Callback<SpinConditions>) -> Html {
+    let (index, fields) = match condition {
+        SpinConditions::Condition(target, scorer) => {
+            let s = scorer.clone();
+            let ontarget = onchange.reform(move |target|
+                SpinConditions::Condition(target, s.clone()));
+            let t = target.clone();
+            let onscorer = onchange.reform(move |scorer|
+                SpinConditions::Condition(t.clone(), scorer));
+            (0, html! { <>
+                {target_field(target, ontarget)}
+                {render_spin_scorer(scorer, onscorer)}
+            </> })
+        }
+        SpinConditions::ChainContext(handler, conditions) =>
+            (1, render_chain_context(handler, conditions, onchange.reform(
+                |(h, c)| SpinConditions::ChainContext(h, c)))),
+        SpinConditions::ZoneChainContext(handler, conditions) =>
+            (2, render_chain_context(handler, conditions, onchange.reform(
+                |(h, c)| SpinConditions::ZoneChainContext(h, c)))),
+        SpinConditions::Sequence(sequence) =>
+            (3, render_sequence(sequence,
+                onchange.reform(SpinConditions::Sequence),
+                render_spin_scorer, || new_spin_scorer(0))),
+    };
+    html! {
+        <div class="condition-editor">
+            {select_field("Type", &SPIN_CONDITIONS, index,
+                onchange.reform(new_spin_condition_of))}
+            {fields}
+        </div>
+    }
+}
+
+// Event conditions
+
+const EVENT_SCORERS: [&str; 8] = [
+    "Line Clears", "Zone Clears", "Lines Cleared", "Pieces Placed",
+    "Holds Used", "Zones Activated", "Rotations", "Inputs",
+];
+
+fn event_line_clear(reqs: LineClearReqs) -> EventScorer {
+    EventScorer::LineClear {
+        req_lines: reqs.req_lines,
+        req_piece: reqs.req_piece,
+        req_all_clear: reqs.req_all_clear,
+        negate: reqs.negate,
+    }
+}
+
+fn new_event_scorer(index: usize) -> EventScorer {
+    match index {
+        0 => event_line_clear(new_line_clear()),
+        1 => EventScorer::ZoneClear { req_lines: ReqOrMin::Min(1) },
+        2 => EventScorer::LinesCleared,
+        3 => EventScorer::PiecesPlaced,
+        4 => EventScorer::HoldsUsed,
+        5 => EventScorer::ZonesActivated,
+        6 => EventScorer::Rotations,
+        _ => EventScorer::Inputs { soft_dropping: false },
+    }
+}
+
+fn render_event_scorer(scorer: &EventScorer,
+        onchange: Callback<EventScorer>) -> Html {
+    let (index, fields) = match scorer {
+        EventScorer::LineClear { req_lines, req_piece, req_all_clear, negate } =>
+            (0, render_line_clear(&LineClearReqs {
+                negate: *negate,
+                req_lines: *req_lines,
+                req_piece: *req_piece,
+                req_all_clear: req_all_clear.clone(),
+                ..new_line_clear()
+            }, Level::Event, onchange.reform(event_line_clear))),
+        EventScorer::ZoneClear { req_lines } =>
+            (1, req_or_min_field("Lines", *req_lines, onchange.reform(
+                |req_lines| EventScorer::ZoneClear { req_lines }))),
+        EventScorer::LinesCleared => (2, html! {}),
+        EventScorer::PiecesPlaced => (3, html! {}),
+        EventScorer::HoldsUsed => (4, html! {}),
+        EventScorer::ZonesActivated => (5, html! {}),
+        EventScorer::Rotations => (6, html! {}),
+        EventScorer::Inputs { .. } => (7, html! {}),
+    };
+    html! { <>
+        {select_field("Count", &EVENT_SCORERS, index,
+            onchange.reform(new_event_scorer))}
+        {fields}
+    </> }
+}
+
+const EVENT_CONDITIONS: [&str; 10] = [
+    "Condition", "3-Corner T-Spins", "Immobile All-Spins", "Puzzle Spins",
+    "All Of", "Any Of", "Not", "At Least", "Sequence", "Board",
+];
+
+fn new_event_condition_of(index: usize) -> EventConditions {
+    match index {
+        0 => EventConditions::Condition(ScoreTarget { score: 0, target: 1 },
+            new_event_scorer(0)),
+        1 => EventConditions::TSpinContext(SpinHandler::new(None), Vec::new()),
+        2 => EventConditions::AllSpinContext(SpinHandler::new(None), Vec::new()),
+        3 => EventConditions::RulesSpinContext(SpinHandler::new(None), Vec::new()),
+        4 => EventConditions::All(Vec::new()),
+        5 => EventConditions::Any(Vec::new()),
+        6 => EventConditions::Not(Box::new(new_event_condition())),
+        7 => EventConditions::AtLeast(1, Vec::new()),
+        8 => EventConditions::Sequence(Sequence::new(Vec::new(), false)),
+        _ => EventConditions::Board(Box::new(BoardCondition::new(
+            new_board_goal(0), false))),
+    }
+}
+
+fn new_event_condition() -> EventConditions {
+    new_event_condition_of(0)
+}
+
+// Board goals, with the target cells painted onto the board
+
+const BOARD_GOALS: [&str; 5] = [
+    "Match Board", "Fill Cells", "Residue", "Max Height", "No Holes",
+];
+
+fn new_board_goal(index: usize) -> BoardGoal {
+    match index {
+        0 => BoardGoal::MatchBoard(BoardMask::new()),
+        1 => BoardGoal::FillCells(BoardMask::new()),
+        2 => BoardGoal::Residue(BoardMask::new()),
+        3 => BoardGoal::MaxHeight(4),
+        _ => BoardGoal::NoHoles,
+    }
+}
+
+fn render_board_condition(condition: &BoardCondition,
+        onchange: Callback<BoardCondition>) -> Html {
+    let (index, fields) = match &condition.goal {
+        BoardGoal::MatchBoard(_) => (0, html! {}),
+        BoardGoal::FillCells(_) => (1, html! {}),
+        BoardGoal::Residue(_) => (2, html! {}),
+        BoardGoal::MaxHeight(height) => {
+            let negate = condition.negate;
+            (3, number_field("Height", *height, onchange.reform(move |height|
+                BoardCondition::new(BoardGoal::MaxHeight(height), negate))))
+        }
+        BoardGoal::NoHoles => (4, html! {}),
+    };
+    // Switching between painted goals keeps the painted cells
+    let goal = condition.goal.clone();
+    let negate = condition.negate;
+    let ongoal = onchange.reform(move |i| {
+        let mut next = new_board_goal(i);
+        if let (Some(mask), Some(target)) = (goal.get_mask(),
+                next.get_mask_mut()) {
+            *target = mask.clone();
+        }
+        BoardCondition::new(next, negate)
+    });
+    let goal = condition.goal.clone();
+    let onnegate = onchange.reform(move |negate|
+        BoardCondition::new(goal.clone(), negate));
+    html! { <>
+        {select_field("Goal", &BOARD_GOALS, index, ongoal)}
+        {fields}
+        {check_field("Negate", condition.negate, onnegate)}
+    </> }
+}
+
+fn render_spin_context(handler: &SpinHandler, conditions: &[SpinConditions],
+        onchange: Callback<(SpinHandler, Vec<SpinConditions>)>) -> Html {
+    let h = handler.clone();
+    render_list(conditions, onchange.reform(move |conditions|
+        (h.clone(), conditions)), render_spin_condition, new_spin_condition)
+}
+
+fn render_event_condition(condition: &EventConditions,
+        onchange: Callback<EventConditions>) -> Html {
+    let (index, fields) = match condition {
+        EventConditions::Condition(target, scorer) => {
+            let s = scorer.clone();
+            let ontarget = onchange.reform(move |target|
+                EventConditions::Condition(target, s.clone()));
+            let t = target.clone();
+            let onscorer = onchange.reform(move |scorer|
+                EventConditions::Condition(t.clone(), scorer));
+            (0, html! { <>
+                {target_field(target, ontarget)}
+                {render_event_scorer(scorer, onscorer)}
+            </> })
+        }
+        EventConditions::TSpinContext(handler, conditions) =>
+            (1, render_spin_context(handler, conditions, onchange.reform(
+                |(h, c)| EventConditions::TSpinContext(h, c)))),
+        EventConditions::AllSpinContext(handler, conditions) =>
+            (2, render_spin_context(handler, conditions, onchange.reform(
+                |(h, c)| EventConditions::AllSpinContext(h, c)))),
+        EventConditions::RulesSpinContext(handler, conditions) =>
+            (3, render_spin_context(handler, conditions, onchange.reform(
+                |(h, c)| EventConditions::RulesSpinContext(h, c)))),
+        EventConditions::All(conditions) =>
+            (4, render_list(conditions, onchange.reform(EventConditions::All),
+                render_event_condition, new_event_condition)),
+        EventConditions::Any(conditions) =>
+            (5, render_list(conditions, onchange.reform(EventConditions::Any),
+                render_event_condition, new_event_condition)),
+        EventConditions::Not(inner) =>
+            (6, render_event_condition(inner, onchange.reform(
+                |c| EventConditions::Not(Box::new(c))))),
+        EventConditions::AtLeast(count, conditions) => {
+            let c = conditions.clone();
+            let oncount = onchange.reform(move |count|
+                EventConditions::AtLeast(count, c.clone()));
+            let count = *count;
+            let onconditions = onchange.reform(move |conditions|
+                EventConditions::AtLeast(count, conditions));
+            (7, html! { <>
+                {number_field("Count", count, oncount)}
+                {render_list(conditions, onconditions,
+                    render_event_condition, new_event_condition)}
+            </> })
+        }
+        EventConditions::Sequence(sequence) =>
+            (8, render_sequence(sequence,
+                onchange.reform(EventConditions::Sequence),
+                render_event_scorer, || new_event_scorer(0))),
+        EventConditions::Board(board) =>
+            (9, render_board_condition(board, onchange.reform(
+                |b| EventConditions::Board(Box::new(b))))),
+    };
+    html! {
+        <div class="condition-editor">
+            {select_field("Type", &EVENT_CONDITIONS, index,
+                onchange.reform(new_event_condition_of))}
+            {fields}
+        </div>
+    }
+}
+
+#[function_component(ConditionsEditor)]
+pub fn conditions_editor(props: &Props) -> Html {
+    html! {
+        <div class="conditions-editor"
+            onkeydown={Callback::from(|e: KeyboardEvent|
+                e.stop_propagation())}
+            onkeyup={Callback::from(|e: KeyboardEvent|
+                e.stop_propagation())}>
+            <p><strong>{props.name.clone()}</strong></p>
+            {render_list(&props.conditions.conditions,
+                props.onchange.reform(|conditions| Conditions { conditions }),
+                render_event_condition, new_event_condition)}
+        </div>
+    }
+}
@@ -67,12 +67,21 @@ pub const BINDABLE_PLAY: [PlayButton; 19] = [
 	PlayButton::Restart, PlayButton::Edit,
 ];
 
-pub const BINDABLE_EDIT: [EditButton; 14] = [
+pub const BINDABLE_EDIT: [EditButton; 28] = [
 	EditButton::SetHold, EditButton::SetCurrent,
 	EditButton::SetNext(1), EditButton::SetNext(2),
 	EditButton::SetNext(3), EditButton::SetNext(4),
 	EditButton::SetBagPos, EditButton::ToggleZone,
-	EditButton::ToggleHoldUsed, EditButton::Play,
+	EditButton::ToggleHoldUsed, EditButton::PaintTarget,
+	EditButton::CycleSpinRules,
+	EditButton::CycleTool, EditButton::RotateStamp,
+	EditButton::ToggleGravity,
+	EditButton::Mirror,
+	EditButton::ShiftLeft, EditButton::ShiftRight,
+	EditButton::ShiftUp, EditButton::ShiftDown,
+	EditButton::RateDifficulty, EditButton::CheckSolutions,
+	EditButton::Undo, EditButton::Redo,
+	EditButton::Play,
 	EditButton::Import, EditButton::Export,
 	EditButton::Revert, EditButton::EraseAll,
 ];
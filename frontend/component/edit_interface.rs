@@ -1,6 +1,7 @@
 
 use std::collections::VecDeque;
 use controller::input_handler::ButtonHandler;
+use controller::edit_history::EditHistory;
 use user_prefs::UserPrefs;
 use ztrix::serialize::DeserializeInput;
 use ztrix::serialize::SerializeUrlSafe;
@@ -16,17 +17,34 @@ use component::piece_box::PieceBoxComponent;
 use component::game::GameButton;
 use component::queue::QueueButton;
 use component::board::BoardMouseEvent;
+use component::board::mino_color;
 use crate::component::game::GameComponent;
+use crate::component::conditions_editor::ConditionsEditor;
 use crate::component::router::Route;
 use ztrix::puzzle::Puzzle;
+use ztrix::condition::event::Conditions;
+use ztrix::condition::event::EventConditions;
+use ztrix::condition::board_state::BoardCondition;
+use ztrix::condition::board_state::BoardGoal;
+use ztrix::condition::progress::spin_rules_name;
+use ztrix::condition::spin::SpinRules;
+use ztrix::validation::Severity;
 
 use yew::prelude::*;
 use serde::Serialize;
 use serde::Deserialize;
 
+use ztrix::game::ActivePiece;
 use ztrix::game::Game;
+use ztrix::game::BoardMask;
 use ztrix::game::Mino;
 use ztrix::game::PieceType;
+use ztrix::position::Rotation;
+use ztrix::position::Vector;
+use ztrix::transform::Mirror;
+use ztrix::difficulty;
+use ztrix::uniqueness;
+use ztrix::uniqueness::SolutionCheck;
 
 fn cycle_piece(piece: PieceType) -> PieceType {
 	match piece {
@@ -61,6 +79,59 @@ fn update_bag(game: &mut Game, advance: usize) {
 		.take(bag_pos).collect();
 }
 
+#[derive(Copy, Clone, PartialEq)]
+pub enum Tool {
+	Cell,
+	Row,
+	Fill,
+	Stamp,
+	InsertRow,
+	DeleteRow,
+	Column,
+}
+
+impl Tool {
+	fn get_name(&self) -> &'static str {
+		match self {
+			Tool::Cell => "Cells",
+			Tool::Row => "Rows",
+			Tool::Fill => "Flood Fill",
+			Tool::Stamp => "Piece Stamp",
+			Tool::InsertRow => "Insert Row",
+			Tool::DeleteRow => "Delete Row",
+			Tool::Column => "Move Garbage Column",
+		}
+	}
+
+	fn next(self) -> Tool {
+		match self {
+			Tool::Cell => Tool::Row,
+			Tool::Row => Tool::Fill,
+			Tool::Fill => Tool::Stamp,
+			Tool::Stamp => Tool::InsertRow,
+			Tool::InsertRow => Tool::DeleteRow,
+			Tool::DeleteRow => Tool::Column,
+			Tool::Column => Tool::Cell,
+		}
+	}
+}
+
+fn rotation_name(rot: Rotation) -> &'static str {
+	match rot {
+		Rotation::Zero => "Spawn",
+		Rotation::Clockwise => "CW",
+		Rotation::Flip => "180",
+		Rotation::Anticlockwise => "CCW",
+	}
+}
+
+const PALETTE: [Option<Mino>; 9] = [
+	None, Some(Mino::Gray),
+	Some(Mino::Piece(PieceType::I)), Some(Mino::Piece(PieceType::O)),
+	Some(Mino::Piece(PieceType::S)), Some(Mino::Piece(PieceType::Z)),
+	Some(Mino::Piece(PieceType::J)), Some(Mino::Piece(PieceType::L)),
+	Some(Mino::Piece(PieceType::T)),
+];
 
 #[derive(Serialize, Deserialize)]
 #[derive(Copy, Clone, Hash, Eq, PartialEq)]
@@ -73,6 +144,20 @@ pub enum EditButton {
 	SetBagPos,
 	ToggleZone,
 	ToggleHoldUsed,
+	PaintTarget,
+	CycleSpinRules,
+	CycleTool,
+	RotateStamp,
+	ToggleGravity,
+	Mirror,
+	ShiftLeft,
+	ShiftRight,
+	ShiftUp,
+	ShiftDown,
+	RateDifficulty,
+	CheckSolutions,
+	Undo,
+	Redo,
 	Play,
 	Import,
 	Export,
@@ -91,6 +176,20 @@ impl EditButton {
             EditButton::SetBagPos => "Set Bag Position",
             EditButton::ToggleZone => "Toggle Zone",
             EditButton::ToggleHoldUsed => "Toggle Hold Used",
+            EditButton::PaintTarget => "Paint Target",
+            EditButton::CycleSpinRules => "Cycle Spin Rules",
+            EditButton::CycleTool => "Cycle Tool",
+            EditButton::RotateStamp => "Rotate Stamp",
+            EditButton::ToggleGravity => "Toggle Stamp Gravity",
+            EditButton::Mirror => "Mirror",
+            EditButton::ShiftLeft => "Shift Left",
+            EditButton::ShiftRight => "Shift Right",
+            EditButton::ShiftUp => "Shift Up",
+            EditButton::ShiftDown => "Shift Down",
+            EditButton::RateDifficulty => "Rate Difficulty",
+            EditButton::CheckSolutions => "Check Solutions",
+            EditButton::Undo => "Undo",
+            EditButton::Redo => "Redo",
             EditButton::Play => "Enter Play Mode",
             EditButton::Import => "Import Link",
             EditButton::Export => "Export Link",
@@ -105,6 +204,11 @@ pub enum Msg {
 	GameButton(ButtonEvent<GameButton>),
 	Button(ButtonEvent<EditButton>),
 	Draw(BoardMouseEvent),
+	SetPalette(Option<Mino>),
+	SetWinConditions(Conditions),
+	SetEndConditions(Conditions),
+	ShowSolution(usize),
+	UseAsHints(usize),
 }
 
 #[derive(Properties, PartialEq)]
@@ -118,8 +222,20 @@ pub struct EditInterface {
 	initial: Puzzle,
 	puzzle: Puzzle,
 	brush: Option<Mino>,
+	palette: Option<Mino>,
+	tool: Tool,
+	stamp_piece: PieceType,
+	stamp_rot: Rotation,
+	gravity: bool,
+	column: Option<usize>,
+	target_brush: bool,
+	painting_target: bool,
 	input: NodeRef,
 	button_handler: ButtonHandler<EditButton>,
+	history: EditHistory,
+	solution_check: Option<SolutionCheck>,
+	// The solution whose first placement is outlined on the board
+	shown_solution: usize,
 }
 
 impl Component for EditInterface {
@@ -132,8 +248,19 @@ impl Component for EditInterface {
 			initial: props.puzzle.clone(),
 			puzzle: props.puzzle.clone(),
 			brush: None,
+			palette: Some(Mino::Gray),
+			tool: Tool::Cell,
+			stamp_piece: PieceType::T,
+			stamp_rot: Rotation::Zero,
+			gravity: true,
+			column: None,
+			target_brush: false,
+			painting_target: false,
 			input: NodeRef::default(),
 			button_handler: ButtonHandler::new(),
+			history: EditHistory::resume(&props.puzzle),
+			solution_check: None,
+			shown_solution: 0,
 		}
 	}
 
@@ -144,6 +271,10 @@ impl Component for EditInterface {
         			|e: ButtonEvent<String>|
         				Msg::KeyButton(e))}>
 	      		<GameComponent puzzle={self.puzzle.clone()}
+	      			hint={self.solution_check.as_ref()
+	      				.and_then(|c| c.solutions.get(self.shown_solution))
+	      				.and_then(|s| s.moves.first())
+	      				.map(|m| m.placement.piece.clone())}
 	      			onboardmouse={ctx.link().callback(
 						move |e: BoardMouseEvent|
 							Msg::Draw(e))}
@@ -174,6 +305,21 @@ impl Component for EditInterface {
 		        			<img src="/assets/zone.png"
 		        				alt="Toggle Zone"/>
 		        		</ButtonComponent>
+		        		<ButtonComponent
+		        			onbutton={ctx.link().callback(
+								|e: ButtonEvent<()>| Msg::Button(
+									e.map(|_| EditButton::PaintTarget)))}>
+		        			<p>{match self.painting_target {
+		        				true => "Paint Board",
+		        				false => "Paint Target",
+		        			}}</p>
+		        		</ButtonComponent>
+		        		<ButtonComponent
+		        			onbutton={ctx.link().callback(
+								|e: ButtonEvent<()>| Msg::Button(
+									e.map(|_| EditButton::CycleSpinRules)))}>
+		        			<p>{spin_rules_name(&self.puzzle.spin_rules)}</p>
+		        		</ButtonComponent>
 	      			</> }}}
 	      			top_right={{ html! { <>
 		        		<ButtonComponent
@@ -184,6 +330,126 @@ impl Component for EditInterface {
 		        				alt="Enter Play Mode"/>
 		        		</ButtonComponent>
 	      			</> }}}/>
+	      		<div class="thin-row palette">
+	      			{for PALETTE.iter().map(|m| {
+	      				let mino = *m;
+	      				html! {
+		      				<ButtonComponent
+		      					onbutton={ctx.link().batch_callback(
+		      						move |e: ButtonEvent<()>| match e {
+		      							ButtonEvent::Press(_) =>
+		      								Some(Msg::SetPalette(mino)),
+		      							_ => None,
+		      						})}>
+		      					<div class={classes!(
+		      						"swatch",
+		      						(mino == self.palette).then_some("selected"),
+		      					)}
+		      						style={format!("background: {}",
+		      							mino.map_or("#111", mino_color))}/>
+		      				</ButtonComponent>
+	      				}
+	      			})}
+	      		</div>
+	      		<div class="thin-row">
+	      			<ButtonComponent
+	      				onbutton={ctx.link().callback(
+							|e: ButtonEvent<()>| Msg::Button(
+								e.map(|_| EditButton::CycleTool)))}>
+	      				<p>{self.tool.get_name()}</p>
+	      			</ButtonComponent>
+	      			<ButtonComponent
+	      				onbutton={ctx.link().callback(
+							|e: ButtonEvent<()>| Msg::Button(
+								e.map(|_| EditButton::RotateStamp)))}>
+	      				<p>{format! {"Stamp: {} {}", self.stamp_piece.serialize(),
+	      					rotation_name(self.stamp_rot)}}</p>
+	      			</ButtonComponent>
+	      			<ButtonComponent
+	      				onbutton={ctx.link().callback(
+							|e: ButtonEvent<()>| Msg::Button(
+								e.map(|_| EditButton::ToggleGravity)))}>
+	      				<p>{match self.gravity {
+	      					true => "Gravity: On",
+	      					false => "Gravity: Off",
+	      				}}</p>
+	      			</ButtonComponent>
+	      		</div>
+	      		<div class="thin-row">
+	      			<ButtonComponent
+	      				onbutton={ctx.link().callback(
+							|e: ButtonEvent<()>| Msg::Button(
+								e.map(|_| EditButton::Mirror)))}>
+	      				<p>{"Mirror"}</p>
+	      			</ButtonComponent>
+	      			<ButtonComponent
+	      				onbutton={ctx.link().callback(
+							|e: ButtonEvent<()>| Msg::Button(
+								e.map(|_| EditButton::ShiftLeft)))}>
+	      				<img src="/assets/left.png"
+	      					alt="Shift Left"/>
+	      			</ButtonComponent>
+	      			<ButtonComponent
+	      				onbutton={ctx.link().callback(
+							|e: ButtonEvent<()>| Msg::Button(
+								e.map(|_| EditButton::ShiftRight)))}>
+	      				<img src="/assets/right.png"
+	      					alt="Shift Right"/>
+	      			</ButtonComponent>
+	      			<ButtonComponent
+	      				onbutton={ctx.link().callback(
+							|e: ButtonEvent<()>| Msg::Button(
+								e.map(|_| EditButton::ShiftUp)))}>
+	      				<img class="flipped" src="/assets/down.png"
+	      					alt="Shift Up"/>
+	      			</ButtonComponent>
+	      			<ButtonComponent
+	      				onbutton={ctx.link().callback(
+							|e: ButtonEvent<()>| Msg::Button(
+								e.map(|_| EditButton::ShiftDown)))}>
+	      				<img src="/assets/down.png"
+	      					alt="Shift Down"/>
+	      			</ButtonComponent>
+	      		</div>
+	      		<div class="thin-row">
+	      			<ButtonComponent
+	      				onbutton={ctx.link().callback(
+							|e: ButtonEvent<()>| Msg::Button(
+								e.map(|_| EditButton::RateDifficulty)))}>
+	      				<p>{match self.puzzle.metadata.difficulty {
+	      					Some(rating) => format! {"Difficulty: {}", rating},
+	      					None => "Rate Difficulty".to_string(),
+	      				}}</p>
+	      			</ButtonComponent>
+	      			<ButtonComponent
+	      				onbutton={ctx.link().callback(
+							|e: ButtonEvent<()>| Msg::Button(
+								e.map(|_| EditButton::CheckSolutions)))}>
+	      				<p>{"Check Solutions"}</p>
+	      			</ButtonComponent>
+	      		</div>
+	      		{match &self.solution_check {
+	      			Some(check) => self.view_solutions(ctx, check),
+	      			None => html! {},
+	      		}}
+	      		<div class="validation">
+	      			{for self.puzzle.validate().iter().map(|i| html! {
+	      				<p class={match i.severity() {
+	      					Severity::Error => "error",
+	      					Severity::Warning => "warning",
+	      				}}>{i.to_string()}</p>
+	      			})}
+	      		</div>
+	      		<ConditionsEditor
+	      			name={"To Win:"}
+	      			conditions={self.puzzle.win_conditions.clone()}
+	      			onchange={ctx.link().callback(
+	      				|c: Conditions| Msg::SetWinConditions(c))}/>
+	      		<ConditionsEditor
+	      			name={"Ends When:"}
+	      			conditions={self.puzzle.end_conditions.clone()}
+	      			onchange={ctx.link().callback(
+	      				|c: Conditions| Msg::SetEndConditions(c))}/>
 	      		<div class="row">
 	      			<input type="text"
 	      				ref={self.input.clone()}
@@ -193,6 +459,22 @@ impl Component for EditInterface {
 	      						e.stop_propagation())}/>
 	      		</div>
 	      		<div class="row">
+	      			<ButtonComponent
+	      				onbutton={ctx.link().callback(
+							move |e: ButtonEvent<()>|
+								Msg::Button(e.map(|_|
+									EditButton::Undo)))}>
+	      				<img src="/assets/undo.png"
+		        				alt="Undo"/>
+	      			</ButtonComponent>
+	      			<ButtonComponent
+	      				onbutton={ctx.link().callback(
+							move |e: ButtonEvent<()>|
+								Msg::Button(e.map(|_|
+									EditButton::Redo)))}>
+	      				<img src="/assets/redo.png"
+		        				alt="Redo"/>
+	      			</ButtonComponent>
 	      			<ButtonComponent
 	      				onbutton={ctx.link().callback(
 							move |e: ButtonEvent<()>|
@@ -235,6 +517,78 @@ impl Component for EditInterface {
 
 	fn update(&mut self, ctx: &Context<Self>,
 			msg: Self::Message) -> bool {
+		// Strokes are recorded once, when the mouse is released
+		let stroke = matches!(msg, Msg::Draw(BoardMouseEvent::Press(_))
+			| Msg::Draw(BoardMouseEvent::Move(_)));
+		let before = self.puzzle.clone();
+		let render = self.apply(ctx, msg);
+		// Undo and redo bring back a recorded puzzle, rating and all
+		let restored = self.history.is_current(&self.puzzle);
+		// Ratings and solutions only hold for the puzzle they were found for
+		let metadata = self.puzzle.metadata.clone();
+		let unchanged = Puzzle { metadata, ..before } == self.puzzle;
+		if !unchanged {
+			if !restored {
+				self.puzzle.metadata.difficulty = None;
+			}
+			self.solution_check = None;
+		}
+		if !stroke && !restored {
+			self.history.record(&self.puzzle);
+		}
+		render
+	}
+}
+
+impl EditInterface {
+	fn view_solutions(&self, ctx: &Context<Self>,
+			check: &SolutionCheck) -> Html {
+		let found = check.solutions.len();
+		html! {
+			<div class="solutions">
+				<p>{match (found, check.complete) {
+					(0, true) => "No solutions".to_string(),
+					(0, false) => "No solution found yet".to_string(),
+					(1, true) => "Unique solution".to_string(),
+					(n, true) => format! {"{} solutions", n},
+					(n, false) => format! {"At least {} solutions", n},
+				}}</p>
+				{for check.solutions.iter().enumerate().map(|(i, s)| {
+					let pieces = s.moves.iter().map(|m| format! {"{}{}",
+						m.placement.piece.piece_type.serialize(),
+						if m.hold { "*" } else { "" }})
+						.collect::<Vec<String>>().join(" ");
+					html! {
+						<div class="thin-row">
+							<ButtonComponent
+								onbutton={ctx.link().batch_callback(
+									move |e: ButtonEvent<()>| match e {
+										ButtonEvent::Release(_) =>
+											Some(Msg::ShowSolution(i)),
+										_ => None,
+									})}>
+								<p class={classes!((i == self.shown_solution)
+									.then_some("selected"))}>
+									{format! {"{}: {}", i + 1, pieces}}
+								</p>
+							</ButtonComponent>
+							<ButtonComponent
+								onbutton={ctx.link().batch_callback(
+									move |e: ButtonEvent<()>| match e {
+										ButtonEvent::Release(_) =>
+											Some(Msg::UseAsHints(i)),
+										_ => None,
+									})}>
+								<p>{"Use as Hints"}</p>
+							</ButtonComponent>
+						</div>
+					}
+				})}
+			</div>
+		}
+	}
+
+	fn apply(&mut self, ctx: &Context<Self>, msg: Msg) -> bool {
 		let user_prefs = UserPrefs::get();
     	let key_bindings = &user_prefs.key_bindings;
     	let event = match msg {
@@ -270,24 +624,131 @@ impl Component for EditInterface {
 					Some(event) => event,
 					None => return false,
     			}
-			Msg::Draw(e) => {
+			Msg::SetPalette(mino) => {
+				self.palette = mino;
+				if let Some(Mino::Piece(piece)) = mino {
+					self.stamp_piece = piece;
+				}
+				return true;
+			}
+			Msg::SetWinConditions(conditions) => {
+				self.puzzle.win_conditions = conditions;
+				return true;
+			}
+			Msg::SetEndConditions(conditions) => {
+				self.puzzle.end_conditions = conditions;
+				return true;
+			}
+			Msg::ShowSolution(i) => {
+				self.shown_solution = i;
+				return true;
+			}
+			Msg::UseAsHints(i) => {
+				if let Some(solution) = self.solution_check.as_ref()
+						.and_then(|c| c.solutions.get(i)) {
+					self.puzzle.metadata.hints = solution.moves.iter()
+						.map(|m| m.placement.piece.clone()).collect();
+				}
+				return true;
+			}
+			Msg::Draw(e) if self.painting_target => {
+				let conditions = &mut self.puzzle.win_conditions;
+				if conditions.get_mask().is_none() {
+					conditions.conditions.push(EventConditions::Board(
+						Box::new(BoardCondition::new(BoardGoal::FillCells(
+							BoardMask::new()), false))));
+				}
+				let mask = conditions.get_mask_mut()
+					.expect("should be a target mask");
 				match e {
 					BoardMouseEvent::Press(pos) => {
-						let board = &mut self.puzzle.game.board;
-						self.brush = match board[pos] {
-							Some(_) => None,
-							None => Some(Mino::Gray),
-						};
-						board[pos] = self.brush;
+						self.target_brush = !mask[pos];
+						mask[pos] = self.target_brush;
 					},
 					BoardMouseEvent::Move(pos) => {
-						let board = &mut self.puzzle.game.board;
-						board[pos] = self.brush;
+						mask[pos] = self.target_brush;
 					},
 					BoardMouseEvent::Release => (),
 				}
 				return true;
 			}
+			// These tools also move the target cells with the board
+			Msg::Draw(e) if matches!(self.tool,
+					Tool::InsertRow | Tool::DeleteRow | Tool::Column) => {
+				match (self.tool, e) {
+					(Tool::InsertRow, BoardMouseEvent::Press(pos)) => {
+						// Leaves a hole where the row was clicked
+						let mut row = [self.palette; 10];
+						row[pos.x as usize] = None;
+						self.puzzle.insert_row(pos.y as usize, row);
+					},
+					(Tool::DeleteRow, BoardMouseEvent::Press(pos)) => {
+						self.puzzle.delete_row(pos.y as usize);
+					},
+					(Tool::Column, BoardMouseEvent::Press(pos)) => {
+						self.column = Some(pos.x as usize);
+					},
+					(Tool::Column, BoardMouseEvent::Move(pos)) => {
+						let to = pos.x as usize;
+						if let Some(from) = self.column {
+							self.puzzle.transpose_garbage(from, to);
+							self.column = Some(to);
+						}
+					},
+					(_, BoardMouseEvent::Release) => {
+						self.column = None;
+					},
+					_ => (),
+				}
+				return true;
+			}
+			Msg::Draw(e) => {
+				let board = &mut self.puzzle.game.board;
+				let palette = self.palette;
+				// Painting over the palette's own colour erases instead
+				let toggled = |mino: Option<Mino>| match mino {
+					m if m == palette => None,
+					_ => palette,
+				};
+				match (self.tool, e) {
+					(Tool::Cell, BoardMouseEvent::Press(pos)) => {
+						self.brush = toggled(board[pos]);
+						board[pos] = self.brush;
+					},
+					(Tool::Cell, BoardMouseEvent::Move(pos)) => {
+						board[pos] = self.brush;
+					},
+					(Tool::Row, BoardMouseEvent::Press(pos)) => {
+						let y = pos.y as usize;
+						self.brush = match board.is_row_full(y) {
+							true => None,
+							false => palette,
+						};
+						board.fill_row(y, self.brush);
+					},
+					(Tool::Row, BoardMouseEvent::Move(pos)) => {
+						board.fill_row(pos.y as usize, self.brush);
+					},
+					(Tool::Fill, BoardMouseEvent::Press(pos)) => {
+						board.flood_fill(pos, toggled(board[pos]));
+					},
+					(Tool::Stamp, BoardMouseEvent::Press(pos)) => {
+						let piece = ActivePiece {
+							piece_type: self.stamp_piece,
+							pos,
+							rot: self.stamp_rot,
+						};
+						let piece = match self.gravity
+								&& !piece.is_colliding(board) {
+							true => piece.get_ghost(board),
+							false => piece,
+						};
+						piece.stamp(board, palette);
+					},
+					_ => (),
+				}
+				return true;
+			}
 		};
 		match event {
 			ButtonEvent::Press(b) => match b {
@@ -327,6 +788,45 @@ impl Component for EditInterface {
 				EditButton::ToggleZone => {
 					self.puzzle.game.in_zone = !self.puzzle.game.in_zone;
 				},
+				EditButton::PaintTarget => {
+					self.painting_target = !self.painting_target;
+				},
+				EditButton::CycleTool =>
+					self.tool = self.tool.next(),
+				EditButton::RotateStamp =>
+					self.stamp_rot = self.stamp_rot + Rotation::Clockwise,
+				EditButton::ToggleGravity =>
+					self.gravity = !self.gravity,
+				EditButton::Mirror =>
+					self.puzzle = self.puzzle.mirror(),
+				EditButton::ShiftLeft =>
+					{ self.puzzle.shift(Vector::ONE_LEFT); }
+				EditButton::ShiftRight =>
+					{ self.puzzle.shift(Vector::ONE_RIGHT); }
+				EditButton::ShiftUp =>
+					{ self.puzzle.shift(Vector::ONE_UP); }
+				EditButton::ShiftDown =>
+					{ self.puzzle.shift(Vector::ONE_DOWN); }
+				EditButton::RateDifficulty => {
+					difficulty::rate(&mut self.puzzle);
+				},
+				EditButton::CheckSolutions => {
+					self.solution_check = Some(uniqueness::check(
+						&self.puzzle, uniqueness::DEFAULT_MAX_SOLUTIONS));
+					self.shown_solution = 0;
+				},
+				EditButton::CycleSpinRules => {
+					let rules = &mut self.puzzle.spin_rules;
+					*rules = match *rules {
+						SpinRules::ThreeCorner { tst_upgrade: true } =>
+							SpinRules::ThreeCorner { tst_upgrade: false },
+						SpinRules::ThreeCorner { tst_upgrade: false } =>
+							SpinRules::Immobile,
+						SpinRules::Immobile => SpinRules::AllMini,
+						SpinRules::AllMini => SpinRules::NoSpins,
+						SpinRules::NoSpins => SpinRules::default(),
+					};
+				},
 				EditButton::Import => {
 					let input = self.input
 						.cast::<HtmlInputElement>()
@@ -379,6 +879,14 @@ impl Component for EditInterface {
 						let _ = clipboard.write_text(&value);
 					}
 				},
+				EditButton::Undo =>
+					if let Some(puzzle) = self.history.undo() {
+						self.puzzle = puzzle;
+					},
+				EditButton::Redo =>
+					if let Some(puzzle) = self.history.redo() {
+						self.puzzle = puzzle;
+					},
 				EditButton::Revert =>
 					self.puzzle = self.initial.clone(),
 				EditButton::EraseAll =>
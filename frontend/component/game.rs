@@ -7,8 +7,10 @@ use component::board::BoardComponent;
 use component::board::BoardMouseEvent;
 use component::conditions::ConditionsComponent;
 
+use ztrix::condition::progress::zone_name;
 use ztrix::puzzle::Puzzle;
 use ztrix::game::Mino;
+use ztrix::game::ActivePiece;
 
 use yew::prelude::*;
 
@@ -26,6 +28,10 @@ pub struct Props {
 	#[prop_or_default]
 	pub last_zone_clear: Option<usize>,
 	#[prop_or_default]
+	pub score: Option<usize>,
+	#[prop_or_default]
+	pub hint: Option<ActivePiece>,
+	#[prop_or_default]
 	pub top_left: Html,
 	#[prop_or_default]
 	pub bottom_left: Html,
@@ -57,6 +63,13 @@ pub fn game_component(props: &Props) -> Html {
 						onbutton={props.onbutton.reform(
 							|e: ButtonEvent<()>| e.map(|_|
 								GameButton::Hold))}/>
+					{match props.score {
+						Some(score) => html! { <>
+							<p><strong>{"SCORE"}</strong></p>
+							<p class="score">{score}</p>
+						</> },
+						None => html! {},
+					}}
 				</div>
 				<div class="bottom-left">
 					<ConditionsComponent
@@ -74,6 +87,8 @@ pub fn game_component(props: &Props) -> Html {
             	<BoardComponent
             		board={game.board.clone()}
 		     		piece={game.piece.clone()}
+		     		target={props.puzzle.win_conditions.get_mask().cloned()}
+		     		hint={props.hint.clone()}
 		     		onmouse={props.onboardmouse.clone()}/>
 					{if props.num_revealed > game.queue.length {
 						html! {
@@ -113,33 +128,9 @@ pub fn game_component(props: &Props) -> Html {
 								}
 							} else {
 								match props.last_zone_clear {
-									Some(l) => match l {
-										5 => "PENTRIX",
-										6 => "HEXTRIX",
-										7 => "SEPTRIX",
-										8 => "OCTORIX",
-										9 => "PENDECATRIX",
-										10 => "DECATRIX",
-										11 => "UNDECATRIX",
-										12 => "DODECATRIX",
-										13 => "TRIDECATRIX",
-										14 => "QUADECATRIX",
-										15 => "DECAPENTRIX",
-										16 => "DECAHEXTRIX",
-										17 => "DECASEPTRIX",
-										18 => "PERFECTRIX",
-										19 => "PENULTIMARIX",
-										20 => "ULTIMATRIX",
-										21 => "KIRBTRIX",
-										22 => "IMPOSSITRIX",
-										23 => "INFINITRIX",
-										24 => "ELECTRIX",
-										25 => "ELECTRIX+",
-										26 => "ELECTRIX++",
-										_ => ""
-									},
-									_ => ""
-								}.to_string()
+									Some(l @ 5..=26) => zone_name(l).to_uppercase(),
+									_ => "".to_string(),
+								}
 							}
 						}</text>
 					</svg>
@@ -14,12 +14,20 @@ use controller::input_handler::TimeHandler;
 use controller::action_handler::ActionHandler;
 
 use component::game::GameComponent;
+use component::piece_box::PieceBoxComponent;
+use component::stats::StatsComponent;
 
 use controller::input_handler::InputEvent;
 
 use yew::prelude::*;
 
+use ztrix::bot::Bot;
+use ztrix::game::Action;
 use ztrix::game::Mino;
+use ztrix::game::PieceType;
+use ztrix::game::game::Event;
+use ztrix::hint::find_hint;
+use ztrix::hint::Hint;
 use ztrix::puzzle::Puzzle;
 use ztrix::replay::Replay;
 
@@ -119,6 +127,11 @@ pub enum Msg {
 	Button(ButtonEvent<PlayButton>),
 	Interval,
 	Config,
+	RerollTo(PieceType),
+	ToggleStats,
+	ViewReplay,
+	ShowMe,
+	Hint,
 }
 
 #[derive(Properties, PartialEq)]
@@ -133,9 +146,30 @@ pub struct PlayInterface {
 	button_handler: ButtonHandler<PlayButton>,
 	time_handler: TimeHandler,
 	action_handler: ActionHandler,
+	// The queue slot whose reroll options are shown, 0 for the current piece
+	reroll_slot: Option<usize>,
+	show_stats: bool,
+	// The hint being shown and the frame it was asked for on
+	hint: Option<(usize, Hint)>,
+	// Kept through undos so they can't be used to dodge the penalty
+	hints_used: usize,
 	_interval: Interval,
 }
 
+impl PlayInterface {
+	// Records any hints the current line is missing, so undoing past one
+	// still costs its penalty
+	fn charge_hints(&mut self) {
+		if self.replay.get_puzzle().over {
+			return;
+		}
+		let charged = self.replay.get_puzzle().scoring.hints;
+		for _ in charged..self.hints_used {
+			self.replay.update(Action::UseHint, &mut |_| ());
+		}
+	}
+}
+
 impl Component for PlayInterface {
     type Message = Msg;
     type Properties = Props;
@@ -147,6 +181,10 @@ impl Component for PlayInterface {
 			button_handler: ButtonHandler::new(),
 			time_handler: TimeHandler::new(),
         	action_handler: ActionHandler::new(),
+        	reroll_slot: None,
+        	show_stats: false,
+        	hint: None,
+        	hints_used: 0,
         	_interval: Interval::new(16, move ||
 				link.send_message(Msg::Interval))
         }
@@ -164,7 +202,9 @@ impl Component for PlayInterface {
             		puzzle={self.replay.get_puzzle().clone()}
             		num_revealed={self.replay.get_num_revealed()}
             		last_zone_clear={self.action_handler.last_zone_clear}
-	      			top_left={{ html! {
+            		score={Some(self.replay.get_puzzle().scoring.total())}
+            		hint={self.hint.as_ref().map(|(_, h)| h.piece.clone())}
+	      			top_left={{ html! { <>
 						<ButtonComponent
 							onbutton={ctx.link().batch_callback(
 								move |e: ButtonEvent<()>| match e {
@@ -175,7 +215,46 @@ impl Component for PlayInterface {
 							<img src="/assets/config.png"
 								alt="Config"/>
 						</ButtonComponent>
-	      				}}}
+						<ButtonComponent
+							onbutton={ctx.link().batch_callback(
+								move |e: ButtonEvent<()>| match e {
+									ButtonEvent::Release(_) =>
+										Some(Msg::ToggleStats),
+									_ => None,
+								})}>
+							<p>{"Stats"}</p>
+						</ButtonComponent>
+						<ButtonComponent
+							onbutton={ctx.link().batch_callback(
+								move |e: ButtonEvent<()>| match e {
+									ButtonEvent::Release(_) =>
+										Some(Msg::ViewReplay),
+									_ => None,
+								})}>
+							<p>{"Replay"}</p>
+						</ButtonComponent>
+						<ButtonComponent
+							onbutton={ctx.link().batch_callback(
+								move |e: ButtonEvent<()>| match e {
+									ButtonEvent::Release(_) =>
+										Some(Msg::ShowMe),
+									_ => None,
+								})}>
+							<p>{"Show Me"}</p>
+						</ButtonComponent>
+						<ButtonComponent
+							onbutton={ctx.link().batch_callback(
+								move |e: ButtonEvent<()>| match e {
+									ButtonEvent::Release(_) =>
+										Some(Msg::Hint),
+									_ => None,
+								})}>
+							<p>{match &self.hint {
+								Some((_, hint)) if hint.hold => "Hint: Hold",
+								_ => "Hint",
+							}}</p>
+						</ButtonComponent>
+	      				</> }}}
 	      			top_right={{ html! {
 		        		<ButtonComponent
 		        			onbutton={ctx.link().callback(
@@ -204,6 +283,40 @@ impl Component for PlayInterface {
 	    			onbutton={ctx.link().callback(
 	        			|e: ButtonEvent<GameButton>|
 	        				Msg::GameButton(e))}/>
+	            {if self.show_stats {
+	            	html! {
+	            		<StatsComponent
+	            			stats={self.replay.get_stats().clone()}
+	            			time={self.replay.get_duration()}/>
+	            	}
+	            } else {
+	            	html! {}
+	            }}
+	            {match self.reroll_slot {
+	            	Some(n) => {
+	            		let back = (self.replay.get_game().queue.fill() + 1)
+	            			.saturating_sub(n);
+	            		html! {
+		            		<div class="row reroll-picker">
+		            			<p>{match n {
+		            				0 => "Reroll Current:".to_owned(),
+		            				n => format! {"Reroll Next #{}:", n},
+		            			}}</p>
+		            			{for self.replay.reroll_options(back).iter().map(|p| html! {
+		            				<PieceBoxComponent
+		            					piece={Some(p)}
+		            					onbutton={ctx.link().batch_callback(
+		            						move |e: ButtonEvent<()>| match e {
+		            							ButtonEvent::Press(_) =>
+		            								Some(Msg::RerollTo(p)),
+		            							_ => None,
+		            						})}/>
+		            			})}
+		            		</div>
+	            		}
+	            	}
+	            	None => html! {},
+	            }}
 	            {for button_bindings.bottom_buttons
 	            	.iter().map(|v| html! {
 	            		<div class="row">
@@ -233,17 +346,80 @@ impl Component for PlayInterface {
 					Some(event) => InputEvent::Button(event),
 					None => return false,
 				}
-    		Msg::GameButton(event) =>
-    			match event.maybe_map(|b| match b {
-    				GameButton::Queue(
-    					QueueButton::NextBox(n)) =>
-    						Some(PlayButton::RerollNext(n+1)),
-    				_ => None,
-    			}).and_then(|e| self.button_handler
-    				.update(e)) {
-					Some(event) => InputEvent::Button(event),
-					None => return false,
+    		Msg::GameButton(ButtonEvent::Press(GameButton::Queue(
+    				QueueButton::NextBox(n)))) => {
+    			self.reroll_slot = match self.reroll_slot {
+    				Some(slot) if slot == n + 1 => None,
+    				_ => Some(n + 1),
+    			};
+    			return true;
+    		}
+    		Msg::GameButton(_) => return false,
+    		// The on-screen button picks the current piece like the next
+    		// boxes do, while its key still rerolls at random
+    		Msg::Button(ButtonEvent::Press(PlayButton::RerollCurrent)) => {
+    			self.reroll_slot = match self.reroll_slot {
+    				Some(0) => None,
+    				_ => Some(0),
+    			};
+    			return true;
+    		}
+    		Msg::Button(ButtonEvent::Release(PlayButton::RerollCurrent)) =>
+    			return false,
+    		Msg::ViewReplay => {
+				let window = web_sys::window()
+					.expect("should be a window");
+				let url = format!{"/replay/{}", self.replay.get_log()};
+				window.open_with_url_and_target(
+					&url, "_blank")
+					.expect("should be able to open url");
+				return false;
+    		}
+    		// The bot plays the next piece for the player, costing a hint
+    		Msg::ShowMe => {
+				self.replay.set_time(
+					self.time_handler.elapsed().as_millis() as usize);
+				self.hint = None;
+				if !self.replay.get_puzzle().over {
+					self.hints_used += 1;
+					self.charge_hints();
+				}
+				let action_handler = &mut self.action_handler;
+				Bot::default().play(&mut self.replay,
+					&mut |e| if let Event::ZoneClear(l) = e {
+						action_handler.last_zone_clear = Some(*l);
+					});
+    			return true;
+    		}
+    		// Outlines the next placement, counting against the score
+    		Msg::Hint => {
+    			let frame = self.replay.get_frame();
+    			if self.hint.as_ref().map_or(false, |(f, _)| *f == frame) {
+    				return false;
+    			}
+    			self.hint = find_hint(self.replay.get_puzzle(),
+    				self.replay.get_stats().pieces)
+    				.map(|hint| (frame, hint));
+    			if self.hint.is_some() {
+    				self.replay.set_time(
+    					self.time_handler.elapsed().as_millis() as usize);
+    				self.hints_used += 1;
+    				self.charge_hints();
     			}
+    			return true;
+    		}
+    		Msg::ToggleStats => {
+    			self.show_stats = !self.show_stats;
+    			return true;
+    		}
+    		Msg::RerollTo(piece) => {
+    			if let Some(n) = self.reroll_slot.take() {
+    				self.hint = None;
+    				self.action_handler.reroll_to(
+    					&mut self.replay, n, piece);
+    			}
+    			return true;
+    		}
     		Msg::Button(event) => match self.button_handler
     				.update(event) {
 					Some(event) => InputEvent::Button(event),
@@ -320,7 +496,19 @@ impl Component for PlayInterface {
 				.expect("should be able to open url");
     	}
     	
+		// A restarted attempt is timed on its own
+		if let InputEvent::Button(ButtonEvent::Press(
+				PlayButton::Restart)) = event {
+			self.time_handler.reset();
+		}
+		self.replay.set_time(
+			self.time_handler.elapsed().as_millis() as usize);
 		self.action_handler.update(&mut self.replay, event);
+		self.charge_hints();
+		let frame = self.replay.get_frame();
+		if self.hint.as_ref().map_or(false, |(f, _)| *f != frame) {
+			self.hint = None;
+		}
 		
 		true	
     }
@@ -1,10 +1,13 @@
 use component::about_interface::AboutInterface;
 use component::config_interface::ConfigInterface;
 use component::play_interface::PlayInterface;
+use component::replay_interface::ReplayInterface;
 use ztrix::game::Game;
+use ztrix::pc_generator::PcSettings;
 use yew::prelude::*;
 use yew_router::prelude::*;
 use ztrix::puzzle::Puzzle;
+use ztrix::replay::TimedLog;
 
 use crate::component::edit_interface::EditInterface;
 
@@ -31,6 +34,11 @@ pub enum Route {
     #[at("/edit/:puzzle")]
     EditPuzzle { puzzle: Puzzle },
 
+    #[at("/replay")]
+    Replay,
+    #[at("/replay/:log")]
+    ReplayLog { log: TimedLog },
+
     #[at("/config")]
     Config,
     #[at("/settings")]
@@ -47,11 +55,24 @@ pub enum Route {
     Random,
     #[at("/random/:difficulty")]
     RandomDifficulty{ difficulty: u32 },
+    #[at("/rated/:rating")]
+    RandomRating{ rating: u32 },
     
     #[at("/tsd")]
     TSDPuzzle,
     #[at("/20tsd")]
     TwentyTSDPuzzle,
+
+    #[at("/pc")]
+    PCPuzzle,
+    #[at("/pc/:height")]
+    PCPuzzleHeight{ height: usize },
+    #[at("/pc/:height/:difficulty")]
+    PCPuzzleDifficulty{ height: usize, difficulty: u32 },
+    #[at("/pc/:height/:difficulty/:pieces")]
+    PCPuzzlePieces{ height: usize, difficulty: u32, pieces: usize },
+    #[at("/pc/:height/:difficulty/:pieces/:hold")]
+    PCPuzzleHold{ height: usize, difficulty: u32, pieces: usize, hold: bool },
 }
 
 fn switch(route: &Route) -> Html {
@@ -77,6 +98,13 @@ fn switch(route: &Route) -> Html {
             <EditInterface puzzle={puzzle.clone()}/>
         },
 
+        Route::Replay => html! {
+            <ReplayInterface/>
+        },
+        Route::ReplayLog { log } => html! {
+            <ReplayInterface log={Some(log.clone())}/>
+        },
+
         Route::Config => html! {
             <ConfigInterface/>
         },
@@ -100,9 +128,41 @@ fn switch(route: &Route) -> Html {
         Route::RandomDifficulty { difficulty } => html! {
             <PlayInterface puzzle={Puzzle::generate_kirb_puzzle(*difficulty)}/>
         },
+        Route::RandomRating { rating } => html! {
+            <PlayInterface puzzle={Puzzle::generate_rated_kirb_puzzle(*rating)}/>
+        },
         Route::TSDPuzzle | Route::TwentyTSDPuzzle => html! {
             <PlayInterface puzzle={Puzzle::generate_tsd_puzzle()}/>
         },
+        Route::PCPuzzle => html! {
+            <PlayInterface puzzle={Puzzle::generate_pc_puzzle(
+                &PcSettings::default())}/>
+        },
+        Route::PCPuzzleHeight { height } => html! {
+            <PlayInterface puzzle={Puzzle::generate_pc_puzzle(
+                &PcSettings::new(*height))}/>
+        },
+        Route::PCPuzzleDifficulty { height, difficulty } => html! {
+            <PlayInterface puzzle={Puzzle::generate_pc_puzzle(&PcSettings {
+                difficulty: *difficulty,
+                ..PcSettings::new(*height)
+            })}/>
+        },
+        Route::PCPuzzlePieces { height, difficulty, pieces } => html! {
+            <PlayInterface puzzle={Puzzle::generate_pc_puzzle(&PcSettings {
+                difficulty: *difficulty,
+                pieces: *pieces,
+                ..PcSettings::new(*height)
+            })}/>
+        },
+        Route::PCPuzzleHold { height, difficulty, pieces, hold } => html! {
+            <PlayInterface puzzle={Puzzle::generate_pc_puzzle(&PcSettings {
+                height: *height,
+                pieces: *pieces,
+                hold: *hold,
+                difficulty: *difficulty,
+            })}/>
+        },
     }
 }
 
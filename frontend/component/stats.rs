@@ -0,0 +1,64 @@
+use yew::prelude::*;
+use enumset::EnumSet;
+use ztrix::game::PieceType;
+use ztrix::serialize::SerializeUrlSafe;
+use ztrix::stats::Stats;
+
+use component::button::ButtonComponent;
+use controller::input_handler::ButtonEvent;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub stats: Stats,
+    // Milliseconds played
+    pub time: usize,
+}
+
+fn export(stats: &Stats, time: usize) {
+    let json = stats.to_json(time);
+    let window = web_sys::window()
+        .expect("should be a window");
+    if let Some(clipboard) = window.navigator().clipboard() {
+        let _ = clipboard.write_text(&json);
+    }
+}
+
+#[function_component(StatsComponent)]
+pub fn stats(props: &Props) -> Html {
+    let stats = &props.stats;
+    let time = props.time;
+    let spins = EnumSet::<PieceType>::all().iter()
+        .filter(|p| stats.spins[*p as usize] + stats.mini_spins[*p as usize] > 0)
+        .map(|p| format! {"{}: {} ({} mini)", p.serialize(),
+            stats.spins[p as usize], stats.mini_spins[p as usize]})
+        .collect::<Vec<String>>();
+    let onexport = {
+        let stats = stats.clone();
+        Callback::from(move |e: ButtonEvent<()>| {
+            if let ButtonEvent::Release(_) = e {
+                export(&stats, time);
+            }
+        })
+    };
+    html! {
+        <div class="stats">
+            <p>{format! {"Time: {:.1}s", time as f64 / 1000.0}}</p>
+            <p>{format! {"Pieces: {} ({:.2} PPS)", stats.pieces, stats.pps(time)}}</p>
+            <p>{format! {"Attack: {} ({:.1} APM)", stats.attack, stats.apm(time)}}</p>
+            <p>{format! {"Lines: {}", stats.lines}}</p>
+            <p>{format! {"Spins: {}", match spins.len() {
+                0 => "None".to_owned(),
+                _ => spins.join(", "),
+            }}}</p>
+            <p>{format! {"Max B2B: {}", stats.max_b2b}}</p>
+            <p>{format! {"Max Combo: {}", stats.max_combo}}</p>
+            <p>{format! {"Finesse Faults: {}", stats.finesse_faults}}</p>
+            <p>{format! {"Holds: {}", stats.holds}}</p>
+            <p>{format! {"Zones: {} ({} lines, best {})",
+                stats.zones, stats.zone_lines, stats.max_zone}}</p>
+            <ButtonComponent onbutton={onexport}>
+                <p>{"Copy JSON"}</p>
+            </ButtonComponent>
+        </div>
+    }
+}
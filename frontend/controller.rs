@@ -2,4 +2,6 @@ pub mod input_handler;
 
 pub mod action_handler;
 
-pub mod input_bindings;
\ No newline at end of file
+pub mod input_bindings;
+
+pub mod edit_history;
\ No newline at end of file
@@ -6,11 +6,13 @@ use controller::input_handler::ButtonEvent;
 use ztrix::game::game::Event;
 use ztrix::replay::Replay;
 use ztrix::game::MaybeActive;
+use ztrix::game::PieceType;
 use user_prefs::UserPrefs;
 
 use std::time::Duration;
 use crate::component::play_interface::PlayButton;
 use ztrix::position::Rotation;
+use ztrix::position::Vector;
 use serde::Serialize;
 use serde::Deserialize;
 
@@ -112,6 +114,17 @@ impl ActionHandler {
 		}
 	}
 
+	// Repeats from held keys are only sent when they would move the piece,
+	// so a piece resting on a wall doesn't rack up blocked inputs
+	fn can_move(replay: &Replay, vec: Vector) -> bool {
+		let game = replay.get_game();
+		if let Some(MaybeActive::Active(active)) = &game.piece {
+			active.clone().try_move(&game.board, vec)
+		} else {
+			false
+		}
+	}
+
 	fn spawn(&mut self, replay: &mut Replay) {
 		replay.update(Action::SpawnPiece(
 	    	self.irs(), self.ihs()),
@@ -171,10 +184,12 @@ impl ActionHandler {
     			self.down_das_timer = handling_settings.down_das_duration;
     			replay.update(Action::MoveDown, &mut |_| ());
     		}
-		    PlayButton::DownFast =>
-				for _ in 0..26 {
+		    PlayButton::DownFast => {
+				replay.update(Action::MoveDown, &mut |_| ());
+				while Self::can_move(replay, Vector::ONE_DOWN) {
 					replay.update(Action::MoveDown, &mut |_| ());
 				}
+			}
 		    PlayButton::Clockwise => {
 		    	replay.update(Action::Rotate(
     				Rotation::Clockwise), &mut |_| ());
@@ -273,6 +288,18 @@ impl ActionHandler {
 		}
 	}
 
+	pub fn reroll_to(&mut self, replay: &mut Replay, n: usize,
+			piece: PieceType) {
+		let user_prefs = UserPrefs::get();
+		let handling_settings = &user_prefs.handling_settings;
+		let back = (replay.get_game().queue.fill() + 1).saturating_sub(n);
+		if replay.reroll_to(back, piece, &mut |_| ()) {
+			self.entry_delay_timer = handling_settings.entry_delay;
+			self.frozen = true;
+			self.moved = false;
+		}
+	}
+
 	pub fn release(&mut self, _replay: &mut Replay,
 			button: PlayButton) {
 		self.held.remove(&button);
@@ -301,11 +328,15 @@ impl ActionHandler {
 			match self.das() {
 				DasDirection::None => (),
 				DasDirection::Left => {
-					replay.update(Action::MoveLeft, &mut |_| ());
+					if Self::can_move(replay, Vector::ONE_LEFT) {
+						replay.update(Action::MoveLeft, &mut |_| ());
+					}
 					self.moved = true;
 				}
 				DasDirection::Right => {
-					replay.update(Action::MoveRight, &mut |_| ());
+					if Self::can_move(replay, Vector::ONE_RIGHT) {
+						replay.update(Action::MoveRight, &mut |_| ());
+					}
 					self.moved = true;
 				}
 			}
@@ -320,7 +351,9 @@ impl ActionHandler {
 		while self.down_das_timer < duration {
 			self.down_das_timer += handling_settings.down_arr_duration;
 			if self.held.contains(&PlayButton::DownSlow) {
-				replay.update(Action::MoveDown, &mut |_| ());
+				if Self::can_move(replay, Vector::ONE_DOWN) {
+					replay.update(Action::MoveDown, &mut |_| ());
+				}
 				self.moved = true;
 			}
 			max_iter -= 1;
@@ -330,7 +363,7 @@ impl ActionHandler {
 		}
 		self.down_das_timer -= duration;
 		if self.held.contains(&PlayButton::DownFast) {
-			for _ in 0..26 {
+			while Self::can_move(replay, Vector::ONE_DOWN) {
 				replay.update(Action::MoveDown, &mut |_| ());
 			}
 			self.moved = true;
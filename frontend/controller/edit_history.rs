@@ -0,0 +1,74 @@
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde::Deserialize;
+use ztrix::puzzle::Puzzle;
+use ztrix::serialize::SerializeUrlSafe;
+
+use yewdux::prelude::*;
+
+const MAX_HISTORY: usize = 200;
+
+// Puzzles are stored serialized so the history survives leaving the editor
+#[derive(Default, Store, Serialize, Deserialize, Clone, PartialEq)]
+#[store(storage = "session")]
+pub struct EditHistory {
+	undo: Vec<String>,
+	redo: Vec<String>,
+	current: String,
+}
+
+impl EditHistory {
+	// Picks up the saved history if it ended on this puzzle
+	pub fn resume(puzzle: &Puzzle) -> Self {
+		let current = puzzle.serialize();
+		let saved = Dispatch::<Self>::new().get();
+		if saved.current == current {
+			return (*saved).clone();
+		}
+		Self {
+			undo: Vec::new(),
+			redo: Vec::new(),
+			current,
+		}
+	}
+
+	fn save(&self) {
+		Dispatch::<Self>::new().set(self.clone());
+	}
+
+	// Whether the puzzle is the last one recorded or undone to
+	pub fn is_current(&self, puzzle: &Puzzle) -> bool {
+		puzzle.serialize() == self.current
+	}
+
+	pub fn record(&mut self, puzzle: &Puzzle) {
+		let current = puzzle.serialize();
+		if current == self.current {
+			return;
+		}
+		let previous = std::mem::replace(&mut self.current, current);
+		self.undo.push(previous);
+		if self.undo.len() > MAX_HISTORY {
+			self.undo.remove(0);
+		}
+		self.redo.clear();
+		self.save();
+	}
+
+	pub fn undo(&mut self) -> Option<Puzzle> {
+		let previous = self.undo.pop()?;
+		let current = std::mem::replace(&mut self.current, previous);
+		self.redo.push(current);
+		self.save();
+		Puzzle::from_str(&self.current).ok()
+	}
+
+	pub fn redo(&mut self) -> Option<Puzzle> {
+		let next = self.redo.pop()?;
+		let current = std::mem::replace(&mut self.current, next);
+		self.undo.push(current);
+		self.save();
+		Puzzle::from_str(&self.current).ok()
+	}
+}
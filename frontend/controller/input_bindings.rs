@@ -78,6 +78,23 @@ impl Default for KeyBindings {
 
 				("KeyC", EditButton::ToggleHoldUsed),
 
+				("KeyP", EditButton::PaintTarget),
+
+				("KeyR", EditButton::CycleSpinRules),
+
+				("KeyT", EditButton::CycleTool),
+				("KeyX", EditButton::RotateStamp),
+				("KeyG", EditButton::ToggleGravity),
+
+				("KeyM", EditButton::Mirror),
+				("ArrowLeft", EditButton::ShiftLeft),
+				("ArrowRight", EditButton::ShiftRight),
+				("ArrowUp", EditButton::ShiftUp),
+				("ArrowDown", EditButton::ShiftDown),
+
+				("KeyZ", EditButton::Undo),
+				("KeyY", EditButton::Redo),
+
 				("KeyS", EditButton::Revert),
 
 				("KeyEscape", EditButton::Play),
@@ -92,6 +92,15 @@ impl TimeHandler {
 		}
 	}
 
+	pub fn elapsed(&self) -> Duration {
+		self.start_time.elapsed()
+	}
+
+	pub fn reset(&mut self) {
+		self.start_time = Instant::now();
+		self.time = Duration::ZERO;
+	}
+
 	pub fn update(&mut self) -> Duration {
 		let prev_time = self.time;
 		self.time = self.start_time.elapsed();
@@ -17,9 +17,21 @@ extern crate instant;
 extern crate gloo_timers;
 extern crate rand;
 use component::router::App;
+use instant::Instant;
+use std::time::Duration;
 use wasm_bindgen::prelude::*;
 
+thread_local! {
+    static START: Instant = Instant::now();
+}
+
+// Lets the solver tell how long it's been searching
+fn now() -> Duration {
+    START.with(|start| start.elapsed())
+}
+
 #[wasm_bindgen(start)]
 pub fn run_app() {
+    ztrix::solver::set_clock(now);
     yew::start_app::<App>();
 }
\ No newline at end of file
@@ -0,0 +1,203 @@
+use crate::game::ActivePiece;
+use crate::game::Board;
+use crate::game::PieceType;
+use crate::position::Position;
+use crate::position::Rotation;
+use crate::position::Vector;
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct Well {
+    pub column: usize,
+    // How far the column sits below its lower neighbour, walls count as full
+    pub depth: usize,
+}
+
+#[derive(Hash, Eq, PartialEq, Copy, Clone)]
+pub enum TSlotKind {
+    // Pointing down, clearing two lines
+    Tsd,
+    // On its side, clearing three lines
+    Tst,
+    // On its side under an overhang, clearing two lines
+    Stsd,
+}
+
+impl TSlotKind {
+    pub fn lines(&self) -> usize {
+        match self {
+            Self::Tsd | Self::Stsd => 2,
+            Self::Tst => 3,
+        }
+    }
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct TSlot {
+    pub kind: TSlotKind,
+    // Where the T has to end up, including the rotation needed
+    pub piece: ActivePiece,
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct BoardAnalysis {
+    pub heights: [usize; 10],
+    pub holes: usize,
+    // Filled cells stacked above a hole in the same column
+    pub covered: usize,
+    // Filled cells with an empty cell directly below
+    pub overhangs: usize,
+    pub bumpiness: usize,
+    pub wells: Vec<Well>,
+    pub row_transitions: usize,
+    pub column_transitions: usize,
+    // Sides of filled cells that touch an empty cell, walls don't count
+    pub exposed_edges: usize,
+    pub t_slots: Vec<TSlot>,
+}
+
+impl BoardAnalysis {
+    pub fn new(board: &Board) -> Self {
+        let heights = column_heights(board);
+        Self {
+            heights,
+            holes: board.holes(),
+            covered: covered_cells(board),
+            overhangs: overhangs(board),
+            bumpiness: bumpiness(&heights),
+            wells: wells(&heights),
+            row_transitions: row_transitions(board),
+            column_transitions: column_transitions(board),
+            exposed_edges: exposed_edges(board),
+            t_slots: t_slots(board),
+        }
+    }
+
+    pub fn max_height(&self) -> usize {
+        self.heights.iter().copied().max().unwrap_or(0)
+    }
+
+    pub fn deepest_well(&self) -> Option<&Well> {
+        self.wells.iter().max_by_key(|w| w.depth)
+    }
+}
+
+pub fn column_heights(board: &Board) -> [usize; 10] {
+    let mut heights = [0; 10];
+    for (x, height) in heights.iter_mut().enumerate() {
+        *height = board.matrix.iter()
+            .rposition(|row| row[x].is_some())
+            .map_or(0, |y| y + 1);
+    }
+    heights
+}
+
+pub fn covered_cells(board: &Board) -> usize {
+    let heights = column_heights(board);
+    (0..10).map(|x| {
+        match (0..heights[x]).find(|y| board.matrix[*y][x].is_none()) {
+            Some(hole) => (hole..heights[x])
+                .filter(|y| board.matrix[*y][x].is_some()).count(),
+            None => 0,
+        }
+    }).sum()
+}
+
+pub fn overhangs(board: &Board) -> usize {
+    (1..26).map(|y| (0..10)
+        .filter(|x| board.matrix[y][*x].is_some()
+            && board.matrix[y-1][*x].is_none())
+        .count()).sum()
+}
+
+pub fn bumpiness(heights: &[usize; 10]) -> usize {
+    heights.windows(2).map(|w| w[0].abs_diff(w[1])).sum()
+}
+
+pub fn wells(heights: &[usize; 10]) -> Vec<Well> {
+    (0..10).filter_map(|x| {
+        let left = if x == 0 { usize::MAX } else { heights[x-1] };
+        let right = if x == 9 { usize::MAX } else { heights[x+1] };
+        let depth = left.min(right).saturating_sub(heights[x]);
+        (depth > 0).then_some(Well { column: x, depth })
+    }).collect()
+}
+
+// Changes between filled and empty along each row, walls count as filled
+pub fn row_transitions(board: &Board) -> usize {
+    (0..board.height()).map(|y| (-1..10)
+        .filter(|x| board[Position::new(*x, y as i32)].is_some()
+            != board[Position::new(*x + 1, y as i32)].is_some())
+        .count()).sum()
+}
+
+// Changes between filled and empty up each column, the floor counts as filled
+pub fn column_transitions(board: &Board) -> usize {
+    (0..10).map(|x| (-1..25)
+        .filter(|y| board[Position::new(x, *y)].is_some()
+            != board[Position::new(x, *y + 1)].is_some())
+        .count()).sum()
+}
+
+pub fn exposed_edges(board: &Board) -> usize {
+    let sides = [Vector::ONE_LEFT, Vector::ONE_RIGHT,
+        Vector::ONE_UP, Vector::ONE_DOWN];
+    (0..26).map(|y| (0..10).map(|x| {
+        let pos = Position::new(x, y);
+        match board[pos] {
+            Some(_) => sides.iter()
+                .filter(|dir| board[pos + **dir].is_none()).count(),
+            None => 0,
+        }
+    }).sum::<usize>()).sum()
+}
+
+// Resting T placements that spin by the 3-corner rule and clear lines
+pub fn t_slots(board: &Board) -> Vec<TSlot> {
+    let mut slots = Vec::new();
+    for y in 0..26 {
+        for x in 0..10 {
+            for rot in [Rotation::Zero, Rotation::Clockwise,
+                    Rotation::Flip, Rotation::Anticlockwise] {
+                let piece = ActivePiece {
+                    piece_type: PieceType::T,
+                    pos: Position::new(x, y),
+                    rot,
+                };
+                if let Some(kind) = t_slot_kind(board, &piece) {
+                    slots.push(TSlot { kind, piece });
+                }
+            }
+        }
+    }
+    slots
+}
+
+fn t_slot_kind(board: &Board, piece: &ActivePiece) -> Option<TSlotKind> {
+    if piece.is_colliding(board)
+            || piece.clone().try_move(board, Vector::ONE_DOWN) {
+        return None;
+    }
+    let corners = [Vector::new(-1, -1), Vector::new(1, -1),
+        Vector::new(-1, 1), Vector::new(1, 1)].iter()
+        .filter(|v| board[piece.pos + v.rotate(piece.rot)].is_some())
+        .count();
+    if corners < 3 {
+        return None;
+    }
+    let positions = piece.get_mino_positions();
+    let mut rows = positions.iter().map(|p| p.y).collect::<Vec<i32>>();
+    rows.sort();
+    rows.dedup();
+    let lines = rows.iter().filter(|y| (0..10).all(|x| {
+        let pos = Position::new(x, **y);
+        positions.contains(&pos) || board[pos].is_some()
+    })).count();
+    match (piece.rot, lines) {
+        (Rotation::Flip, 2) => Some(TSlotKind::Tsd),
+        (Rotation::Clockwise | Rotation::Anticlockwise, 3) =>
+            Some(TSlotKind::Tst),
+        (Rotation::Clockwise | Rotation::Anticlockwise, 2) =>
+            Some(TSlotKind::Stsd),
+        _ => None,
+    }
+}
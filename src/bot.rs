@@ -0,0 +1,272 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board_analysis::BoardAnalysis;
+use crate::board_analysis::TSlotKind;
+use crate::condition::chain::ChainEvent;
+use crate::condition::spin::SpinHandler;
+use crate::condition::spin::SpinType;
+use crate::game::Action;
+use crate::game::game::Event;
+use crate::game::game::LineClear;
+use crate::puzzle::Puzzle;
+use crate::replay::Replay;
+use crate::search;
+use crate::search::Placement;
+use crate::serialize::DeserializeError;
+use crate::serialize::DeserializeInput;
+use crate::serialize::SerializeUrlSafe;
+
+// Deeper wells stop being worth more past a tetris
+const MAX_WELL_DEPTH: usize = 4;
+
+// Each term is multiplied by its weight, and the highest total wins
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct Weights {
+    pub height: i32,
+    pub holes: i32,
+    pub covered: i32,
+    pub bumpiness: i32,
+    pub row_transitions: i32,
+    pub column_transitions: i32,
+    pub well_depth: i32,
+    // Open TSD and TST slots left on the board
+    pub t_slots: i32,
+    // Indexed by lines cleared without a spin
+    pub clears: [i32; 5],
+    // Per line cleared with a full spin
+    pub spin: i32,
+    pub b2b: i32,
+    pub combo: i32,
+    pub attack: i32,
+    // Per line waiting at the bottom while in the zone
+    pub zone: i32,
+}
+
+impl Weights {
+    // Keeps the stack low and clean, taking tetrises when they come
+    pub fn survival() -> Self {
+        Self {
+            height: -40,
+            holes: -400,
+            covered: -60,
+            bumpiness: -25,
+            row_transitions: -20,
+            column_transitions: -40,
+            well_depth: 15,
+            t_slots: 0,
+            clears: [0, -40, -20, 0, 400],
+            spin: 100,
+            b2b: 50,
+            combo: 40,
+            attack: 20,
+            zone: 60,
+        }
+    }
+
+    // Builds and keeps T-spin slots, burning as few lines as it can
+    pub fn t_spin() -> Self {
+        Self {
+            height: -25,
+            holes: -350,
+            covered: -50,
+            bumpiness: -20,
+            row_transitions: -15,
+            column_transitions: -35,
+            well_depth: 5,
+            t_slots: 250,
+            clears: [0, -200, -150, -100, 300],
+            spin: 500,
+            b2b: 200,
+            combo: 30,
+            attack: 60,
+            zone: 60,
+        }
+    }
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self::survival()
+    }
+}
+
+impl SerializeUrlSafe for Weights {
+    fn serialize(&self) -> String {
+        format! {"{}{}{}{}{}{}{}{}{}{}{}{}{}{}",
+            self.height.serialize(),
+            self.holes.serialize(),
+            self.covered.serialize(),
+            self.bumpiness.serialize(),
+            self.row_transitions.serialize(),
+            self.column_transitions.serialize(),
+            self.well_depth.serialize(),
+            self.t_slots.serialize(),
+            self.clears.serialize(),
+            self.spin.serialize(),
+            self.b2b.serialize(),
+            self.combo.serialize(),
+            self.attack.serialize(),
+            self.zone.serialize(),
+        }
+    }
+
+    fn deserialize(input: &mut DeserializeInput) -> Result<Self, DeserializeError> {
+        Ok(Self {
+            height: i32::deserialize(input)?,
+            holes: i32::deserialize(input)?,
+            covered: i32::deserialize(input)?,
+            bumpiness: i32::deserialize(input)?,
+            row_transitions: i32::deserialize(input)?,
+            column_transitions: i32::deserialize(input)?,
+            well_depth: i32::deserialize(input)?,
+            t_slots: i32::deserialize(input)?,
+            clears: <[i32; 5]>::deserialize(input)?,
+            spin: i32::deserialize(input)?,
+            b2b: i32::deserialize(input)?,
+            combo: i32::deserialize(input)?,
+            attack: i32::deserialize(input)?,
+            zone: i32::deserialize(input)?,
+        })
+    }
+}
+
+impl fmt::Display for Weights {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.serialize())
+    }
+}
+
+impl FromStr for Weights {
+    type Err = DeserializeError;
+    fn from_str(string: &str) -> Result<Self, DeserializeError> {
+        Self::deserialize_string(string)
+    }
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct BotMove {
+    pub hold: bool,
+    pub placement: Placement,
+    pub score: i64,
+    // Everything from spawning or holding through to PlacePiece
+    pub actions: Vec<Action>,
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct Bot {
+    pub weights: Weights,
+}
+
+impl Bot {
+    pub fn new(weights: Weights) -> Self {
+        Self { weights }
+    }
+
+    // Scores the clear a placement makes and the board it leaves behind
+    pub fn evaluate(&self, puzzle: &Puzzle, placement: &Placement) -> i64 {
+        let weights = &self.weights;
+        let game = &puzzle.game;
+        let bottom = placement.piece.get_mino_positions().iter()
+            .map(|p| p.y).min().unwrap_or(0);
+        if bottom >= 20 && !game.in_zone {
+            return i64::MIN;
+        }
+        let mut board = game.board.clone();
+        placement.piece.clone().place(&mut board);
+        let lines = match game.in_zone {
+            true => board.clear_lines_zone(),
+            false => board.clear_lines(),
+        };
+        let event = Event::LineClear(LineClear {
+            lines,
+            active: placement.piece.clone(),
+            board: game.board.clone(),
+            in_zone: game.in_zone,
+        });
+        let mut spin_handler = SpinHandler::new(placement.kick);
+        let mut chain_handler = puzzle.scoring.get_chain().clone();
+        let mut score = 0;
+        if let Some(spin_event) = spin_handler.handle_spin(&event, &puzzle.spin_rules) {
+            if let Some(chain_event) = chain_handler.handle_zone(&spin_event) {
+                if let ChainEvent::LineClear(chain_clear) = &chain_event {
+                    let spin_clear = chain_clear.clear;
+                    score += match spin_clear.spin {
+                        Some(SpinType::Full) => weights.spin * lines as i32,
+                        _ => weights.clears[lines.min(4)],
+                    } as i64;
+                    if chain_clear.b2b {
+                        score += weights.b2b as i64;
+                    }
+                    if lines > 0 {
+                        score += weights.combo as i64 * chain_clear.combo as i64;
+                    }
+                }
+                score += weights.attack as i64
+                    * puzzle.attack_table().attack(&chain_event) as i64;
+            }
+        }
+
+        let analysis = BoardAnalysis::new(&board);
+        let t_slots = analysis.t_slots.iter()
+            .filter(|s| s.kind != TSlotKind::Stsd).count();
+        let well_depth = analysis.deepest_well()
+            .map_or(0, |w| w.depth.min(MAX_WELL_DEPTH));
+        let zone_lines = match game.in_zone {
+            true => board.matrix.iter()
+                .take_while(|row| row.iter().all(|m| m.is_some())).count(),
+            false => 0,
+        };
+        score += [
+            (weights.height, analysis.max_height()),
+            (weights.holes, analysis.holes),
+            (weights.covered, analysis.covered),
+            (weights.bumpiness, analysis.bumpiness),
+            (weights.row_transitions, analysis.row_transitions),
+            (weights.column_transitions, analysis.column_transitions),
+            (weights.well_depth, well_depth),
+            (weights.t_slots, t_slots),
+            (weights.zone, zone_lines),
+        ].iter().map(|(w, v)| *w as i64 * *v as i64).sum::<i64>();
+        score
+    }
+
+    // The best placement for the current piece or the one hold would give
+    pub fn find_move(&self, puzzle: &Puzzle) -> Option<BotMove> {
+        if puzzle.over {
+            return None;
+        }
+        search::moves(&puzzle.game, true).into_iter()
+            .map(|m| BotMove {
+                score: self.evaluate(puzzle, &m.placement),
+                hold: m.hold,
+                placement: m.placement,
+                actions: m.actions,
+            })
+            .fold(None, |best: Option<BotMove>, m| match best {
+                Some(best) if best.score >= m.score => Some(best),
+                _ => Some(m),
+            })
+    }
+
+    // Plays the next piece into the replay as its own frame
+    pub fn play<F>(&self, replay: &mut Replay, event_handler: &mut F) -> bool
+    where   F: FnMut(&Event) {
+        match self.find_move(replay.get_puzzle()) {
+            Some(bot_move) => {
+                for action in bot_move.actions {
+                    replay.update(action, event_handler);
+                }
+                replay.new_frame();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Default for Bot {
+    fn default() -> Self {
+        Self::new(Weights::default())
+    }
+}
@@ -2,4 +2,8 @@ pub mod all_clear;
 
 pub mod event;
 pub mod spin;
-pub mod chain;
\ No newline at end of file
+pub mod chain;
+pub mod attack;
+pub mod sequence;
+pub mod board_state;
+pub mod progress;
\ No newline at end of file
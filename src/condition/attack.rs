@@ -0,0 +1,261 @@
+use crate::condition::all_clear::AllClearType;
+use crate::condition::chain::ChainEvent;
+use crate::condition::spin::SpinType;
+use crate::serialize::DeserializeError;
+use crate::serialize::SerializeUrlSafe;
+
+#[derive(Hash, Eq, PartialEq, Copy, Clone, Debug)]
+pub enum ComboMode {
+    // Combo damage is added on top of the clear's damage
+    Add,
+    // The larger of the clear's damage and the combo damage is sent
+    Max,
+    // The clear's damage is scaled by the combo, as in TETR.IO
+    Multiply,
+}
+
+impl SerializeUrlSafe for ComboMode {
+    fn serialize(&self) -> String {
+        match self {
+            Self::Add => "A",
+            Self::Max => "M",
+            Self::Multiply => "X",
+        }.to_owned()
+    }
+
+    fn deserialize(input: &mut crate::serialize::DeserializeInput) -> Result<Self, crate::serialize::DeserializeError> {
+        Ok(match input.next()? {
+            'A' => Self::Add,
+            'M' => Self::Max,
+            'X' => Self::Multiply,
+            _ => return Err(DeserializeError::new("ComboMode should be represented by A, M, or X.")),
+        })
+    }
+}
+
+#[derive(Hash, Eq, PartialEq, Copy, Clone, Debug)]
+pub enum AllClearBonus {
+    None,
+    Replace(usize),
+    Add(usize),
+}
+
+impl SerializeUrlSafe for AllClearBonus {
+    fn serialize(&self) -> String {
+        match self {
+            Self::None => "N".to_owned(),
+            Self::Replace(damage) => format! {"R{}", damage.serialize()},
+            Self::Add(damage) => format! {"A{}", damage.serialize()},
+        }
+    }
+
+    fn deserialize(input: &mut crate::serialize::DeserializeInput) -> Result<Self, crate::serialize::DeserializeError> {
+        Ok(match input.next()? {
+            'N' => Self::None,
+            'R' => Self::Replace(usize::deserialize(input)?),
+            'A' => Self::Add(usize::deserialize(input)?),
+            _ => return Err(DeserializeError::new("AllClearBonus should be represented by N, R, or A.")),
+        })
+    }
+}
+
+// Tables are indexed by lines cleared, by combo count or by b2b chain
+// length, and any index past the end of a table uses its last entry.
+// An empty b2b chain table sends the flat b2b bonus instead.
+#[derive(Hash, Eq, PartialEq, Clone, Debug)]
+pub struct AttackTable {
+    pub clears: Vec<usize>,
+    pub spins: Vec<usize>,
+    pub minis: Vec<usize>,
+    pub b2b: usize,
+    pub b2b_chain: Vec<usize>,
+    pub combo: Vec<usize>,
+    pub zone_combo: Vec<usize>,
+    pub combo_mode: ComboMode,
+    pub all_clear: AllClearBonus,
+    pub zone: Vec<usize>,
+}
+
+fn lookup(table: &[usize], index: usize) -> usize {
+    table.get(index).or(table.last()).copied().unwrap_or(0)
+}
+
+impl AttackTable {
+    pub fn tetrio() -> Self {
+        Self {
+            clears: vec![0, 0, 1, 2, 4],
+            spins: vec![0, 2, 4, 6],
+            minis: vec![0, 0, 1],
+            b2b: 1,
+            b2b_chain: (0..=67).map(|chain| match chain {
+                0 => 0,
+                1..=2 => 1,
+                3..=7 => 2,
+                8..=23 => 3,
+                24..=66 => 4,
+                _ => 5,
+            }).collect(),
+            combo: Vec::new(),
+            zone_combo: Vec::new(),
+            combo_mode: ComboMode::Multiply,
+            all_clear: AllClearBonus::Add(10),
+            zone: Vec::new(),
+        }
+    }
+
+    pub fn puyo_puyo_tetris() -> Self {
+        Self {
+            clears: vec![0, 0, 1, 2, 4],
+            spins: vec![0, 2, 4, 6],
+            minis: vec![0, 0, 1],
+            b2b: 1,
+            b2b_chain: Vec::new(),
+            combo: vec![0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 4, 5],
+            zone_combo: Vec::new(),
+            combo_mode: ComboMode::Add,
+            all_clear: AllClearBonus::Replace(10),
+            zone: Vec::new(),
+        }
+    }
+
+    pub fn jstris() -> Self {
+        Self {
+            clears: vec![0, 0, 1, 2, 4],
+            spins: vec![0, 2, 4, 6],
+            minis: vec![0, 0, 1],
+            b2b: 1,
+            b2b_chain: Vec::new(),
+            combo: vec![0, 0, 0, 1, 1, 1, 2, 2, 3, 3, 4, 4, 4, 5],
+            zone_combo: Vec::new(),
+            combo_mode: ComboMode::Add,
+            all_clear: AllClearBonus::Add(10),
+            zone: Vec::new(),
+        }
+    }
+
+    pub fn tec(count_zone_damage: bool) -> Self {
+        let (zone_combo, zone) = match count_zone_damage {
+            false => (Vec::new(), Vec::new()),
+            true => (
+                vec![0, 0, 0, 1, 1, 2, 2, 2, 2, 2, 2, 3],
+                (0..=26).map(|lines| match lines {
+                    20 => 17 + 17,
+                    21 => 18 + 18 + 10,
+                    22 => 20 + 20 + 12 + 10,
+                    23 => 20 + 20 + 20 + 20,
+                    _ => lines + lines / 2,
+                }).collect(),
+            ),
+        };
+        Self {
+            clears: vec![0, 0, 1, 2, 4],
+            spins: vec![0, 2, 4, 6, 8],
+            minis: vec![0, 0, 1, 2, 4],
+            b2b: 1,
+            b2b_chain: Vec::new(),
+            combo: vec![0, 0, 0, 1, 1, 2, 2, 2, 3, 3, 3, 3, 3, 3, 4],
+            zone_combo,
+            combo_mode: ComboMode::Add,
+            all_clear: AllClearBonus::Replace(10),
+            zone,
+        }
+    }
+
+    pub fn jeapordy() -> Self {
+        Self {
+            clears: vec![0, 0, 1, 2, 4],
+            spins: vec![0, 2, 4, 6, 8],
+            minis: vec![0, 1, 3, 5, 7],
+            b2b: 1,
+            b2b_chain: Vec::new(),
+            combo: vec![0, 0, 0, 1, 1, 2, 3, 4, 3, 2],
+            zone_combo: Vec::new(),
+            combo_mode: ComboMode::Max,
+            all_clear: AllClearBonus::Replace(8),
+            zone: Vec::new(),
+        }
+    }
+
+    pub fn attack(&self, event: &ChainEvent) -> usize {
+        match event {
+            ChainEvent::LineClear(chain_clear) => {
+                let spin_clear = chain_clear.clear;
+                let clear = spin_clear.clear;
+                if clear.lines == 0 {
+                    return 0;
+                }
+                let is_all_clear = AllClearType::from_line_clear(clear)
+                    == AllClearType::ALL_CLEAR;
+                if let (true, AllClearBonus::Replace(damage))
+                        = (is_all_clear, self.all_clear) {
+                    return damage;
+                }
+                let mut damage = lookup(match spin_clear.spin {
+                    Some(SpinType::Full) => &self.spins,
+                    Some(SpinType::Mini) => &self.minis,
+                    None => &self.clears,
+                }, clear.lines);
+                if chain_clear.b2b {
+                    damage += match self.b2b_chain.is_empty() {
+                        true => self.b2b,
+                        false => lookup(&self.b2b_chain, chain_clear.b2b_chain),
+                    };
+                }
+                let combo = match clear.in_zone && !self.zone_combo.is_empty() {
+                    true => &self.zone_combo,
+                    false => &self.combo,
+                };
+                let combo_damage = lookup(combo, chain_clear.combo);
+                let damage = match self.combo_mode {
+                    ComboMode::Add => damage + combo_damage,
+                    ComboMode::Max => damage.max(combo_damage),
+                    ComboMode::Multiply => {
+                        let combo = chain_clear.combo.saturating_sub(1) as f64;
+                        if damage > 0 {
+                            (damage as f64 * (1.0 + 0.25 * combo)) as usize
+                        } else {
+                            (1.0 + 1.25 * combo).ln() as usize
+                        }
+                    }
+                };
+                match (is_all_clear, self.all_clear) {
+                    (true, AllClearBonus::Add(bonus)) => damage + bonus,
+                    _ => damage,
+                }
+            }
+            ChainEvent::ZoneClear(lines) => lookup(&self.zone, *lines),
+        }
+    }
+}
+
+impl SerializeUrlSafe for AttackTable {
+    fn serialize(&self) -> String {
+        format! {"{}{}{}{}{}{}{}{}{}{}",
+            self.clears.serialize(),
+            self.spins.serialize(),
+            self.minis.serialize(),
+            self.b2b.serialize(),
+            self.b2b_chain.serialize(),
+            self.combo.serialize(),
+            self.zone_combo.serialize(),
+            self.combo_mode.serialize(),
+            self.all_clear.serialize(),
+            self.zone.serialize(),
+        }
+    }
+
+    fn deserialize(input: &mut crate::serialize::DeserializeInput) -> Result<Self, crate::serialize::DeserializeError> {
+        Ok(Self {
+            clears: Vec::deserialize(input)?,
+            spins: Vec::deserialize(input)?,
+            minis: Vec::deserialize(input)?,
+            b2b: usize::deserialize(input)?,
+            b2b_chain: Vec::deserialize(input)?,
+            combo: Vec::deserialize(input)?,
+            zone_combo: Vec::deserialize(input)?,
+            combo_mode: ComboMode::deserialize(input)?,
+            all_clear: AllClearBonus::deserialize(input)?,
+            zone: Vec::deserialize(input)?,
+        })
+    }
+}
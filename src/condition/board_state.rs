@@ -0,0 +1,120 @@
+use crate::game::Board;
+use crate::game::BoardMask;
+use crate::game::Mino;
+use crate::game::game::Event;
+use crate::serialize::DeserializeError;
+use crate::serialize::SerializeUrlSafe;
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub enum BoardGoal {
+    // Every cell's occupancy equals the target
+    MatchBoard(BoardMask),
+    // Every highlighted cell is occupied
+    FillCells(BoardMask),
+    // The colored minos left behind equal the target
+    Residue(BoardMask),
+    MaxHeight(usize),
+    NoHoles,
+}
+
+impl BoardGoal {
+    pub fn is_met(&self, board: &Board) -> bool {
+        match self {
+            Self::MatchBoard(target) => board.mask() == *target,
+            Self::FillCells(target) => board.matrix.iter().zip(target.matrix.iter())
+                .all(|(row, target)| row.iter().zip(target.iter())
+                    .all(|(m, t)| !t || m.is_some())),
+            Self::Residue(target) => board.matrix.iter().zip(target.matrix.iter())
+                .all(|(row, target)| row.iter().zip(target.iter())
+                    .all(|(m, t)| *t == matches!(m, Some(Mino::Piece(_))))),
+            Self::MaxHeight(height) => board.height() <= *height,
+            Self::NoHoles => board.holes() == 0,
+        }
+    }
+
+    pub fn get_mask(&self) -> Option<&BoardMask> {
+        match self {
+            Self::MatchBoard(target) => Some(target),
+            Self::FillCells(target) => Some(target),
+            Self::Residue(target) => Some(target),
+            _ => None,
+        }
+    }
+
+    pub fn get_mask_mut(&mut self) -> Option<&mut BoardMask> {
+        match self {
+            Self::MatchBoard(target) => Some(target),
+            Self::FillCells(target) => Some(target),
+            Self::Residue(target) => Some(target),
+            _ => None,
+        }
+    }
+}
+
+impl SerializeUrlSafe for BoardGoal {
+    fn serialize(&self) -> String {
+        match self {
+            Self::MatchBoard(target) => format! {"M{}", target.serialize()},
+            Self::FillCells(target) => format! {"F{}", target.serialize()},
+            Self::Residue(target) => format! {"R{}", target.serialize()},
+            Self::MaxHeight(height) => format! {"H{}", height.serialize()},
+            Self::NoHoles => "N".to_owned(),
+        }
+    }
+
+    fn deserialize(input: &mut crate::serialize::DeserializeInput) -> Result<Self, crate::serialize::DeserializeError> {
+        Ok(match input.next()? {
+            'M' => Self::MatchBoard(BoardMask::deserialize(input)?),
+            'F' => Self::FillCells(BoardMask::deserialize(input)?),
+            'R' => Self::Residue(BoardMask::deserialize(input)?),
+            'H' => Self::MaxHeight(usize::deserialize(input)?),
+            'N' => Self::NoHoles,
+            _ => return Err(DeserializeError::new("BoardGoal type should be represented by M, F, R, H, or N.")),
+        })
+    }
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct BoardCondition {
+    pub goal: BoardGoal,
+    pub negate: bool,
+    pub met: bool,
+}
+
+impl BoardCondition {
+    pub fn new(goal: BoardGoal, negate: bool) -> Self {
+        Self {
+            goal,
+            negate,
+            met: false,
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &Event) {
+        if let Event::LineClear(clear) = event {
+            self.met = self.goal.is_met(&clear.board_after()) != self.negate;
+        }
+    }
+
+    pub fn statuses(&self) -> Vec<bool> {
+        vec![self.met]
+    }
+}
+
+impl SerializeUrlSafe for BoardCondition {
+    fn serialize(&self) -> String {
+        format! {"{}{}{}",
+            self.goal.serialize(),
+            self.negate.serialize(),
+            self.met.serialize(),
+        }
+    }
+
+    fn deserialize(input: &mut crate::serialize::DeserializeInput) -> Result<Self, crate::serialize::DeserializeError> {
+        Ok(Self {
+            goal: BoardGoal::deserialize(input)?,
+            negate: bool::deserialize(input)?,
+            met: bool::deserialize(input)?,
+        })
+    }
+}
@@ -1,10 +1,15 @@
 use crate::condition::all_clear::AllClearType;
+use crate::condition::attack::AttackTable;
 use crate::condition::event::ReqOrMin;
 use crate::condition::event::ScoreTarget;
+use crate::condition::sequence::Scorer;
+use crate::condition::sequence::Sequence;
+use crate::condition::sequence::StepEvent;
 use crate::condition::spin::SpinClear;
 use crate::condition::spin::SpinEvent;
 use crate::condition::spin::SpinType;
 use crate::game::PieceType;
+use crate::scoring;
 use crate::serialize::DeserializeError;
 use crate::serialize::SerializeUrlSafe;
 
@@ -12,6 +17,8 @@ use crate::serialize::SerializeUrlSafe;
 pub struct ChainClear<'a> {
     pub clear: &'a SpinClear<'a>,
     pub b2b: bool,
+    // How many back-to-backs in a row this clear makes, starting at 1
+    pub b2b_chain: usize,
     pub combo: usize,
 }
 
@@ -21,15 +28,36 @@ pub enum ChainEvent<'a> {
     ZoneClear(usize),
 }
 
+impl StepEvent for ChainEvent<'_> {
+    fn is_clear(&self) -> bool {
+        match self {
+            ChainEvent::LineClear(chain_clear) =>
+                chain_clear.clear.clear.lines > 0,
+            ChainEvent::ZoneClear(lines) => *lines > 0,
+        }
+    }
+}
+
 #[derive(Hash, Eq, PartialEq, Clone)]
 pub struct ChainHandler {
     pub b2b: bool,
+    pub b2b_chain: usize,
     pub combo: usize,
 }
 
 impl ChainHandler {
     pub fn new(b2b: bool, combo: usize) -> Self {
-        Self {b2b, combo}
+        Self {b2b, b2b_chain: 0, combo}
+    }
+
+    fn update_b2b(&mut self, hard: bool) -> bool {
+        let b2b = self.b2b && hard;
+        self.b2b = hard;
+        self.b2b_chain = match b2b {
+            true => self.b2b_chain + 1,
+            false => 0,
+        };
+        b2b
     }
 
     pub fn handle_zone<'a>(&mut self, event: &'a SpinEvent<'a>)
@@ -38,11 +66,11 @@ impl ChainHandler {
             SpinEvent::LineClear(spin_clear) => {
                 if spin_clear.clear.lines > 0 {
                     self.combo += 1;
-                    let b2b = self.b2b;
-                    self.b2b = spin_clear.hard;
+                    let b2b = self.update_b2b(spin_clear.hard);
                     Some(ChainEvent::LineClear(ChainClear {
                         clear: spin_clear,
-                        b2b: b2b && self.b2b,
+                        b2b,
+                        b2b_chain: self.b2b_chain,
                         combo: self.combo,
                     }))
                 } else {
@@ -50,13 +78,14 @@ impl ChainHandler {
                     Some(ChainEvent::LineClear(ChainClear {
                         clear: spin_clear,
                         b2b: false,
+                        b2b_chain: 0,
                         combo: self.combo,
                     }))
                 }
                 
             },
             &SpinEvent::ZoneClear(lines) => {
-                self.b2b = lines >= 4;
+                self.update_b2b(lines >= 4);
                 if lines > 0 {
                     self.combo += 1;
                 } else {
@@ -76,17 +105,28 @@ impl ChainHandler {
     }
 }
 
+// A running back-to-back chain is written in place of the b2b flag, so
+// handlers without one keep their old encoding
 impl SerializeUrlSafe for ChainHandler {
     fn serialize(&self) -> String {
+        let b2b = match self.b2b_chain {
+            0 => self.b2b.serialize(),
+            chain => format! {"C{}", chain.serialize()},
+        };
         format! {"{}{}",
-            self.b2b.serialize(),
+            b2b,
             self.combo.serialize(),
         }
     }
 
     fn deserialize(input: &mut crate::serialize::DeserializeInput) -> Result<Self, crate::serialize::DeserializeError> {
+        let (b2b, b2b_chain) = match input.next_if('C')? {
+            true => (true, usize::deserialize(input)?),
+            false => (bool::deserialize(input)?, 0),
+        };
         Ok(Self {
-            b2b: bool::deserialize(input)?,
+            b2b,
+            b2b_chain,
             combo: usize::deserialize(input)?,
         })
     }
@@ -95,6 +135,7 @@ impl SerializeUrlSafe for ChainHandler {
 #[derive(Hash, Eq, PartialEq, Clone)]
 pub enum ChainConditions {
     Condition(ScoreTarget, ChainScorer),
+    Sequence(Sequence<ChainScorer>),
 }
 
 impl ChainConditions {
@@ -102,12 +143,29 @@ impl ChainConditions {
         match self {
             Self::Condition(target, scorer) =>
                 target.score += scorer.score_event(event),
+            Self::Sequence(sequence) => sequence.handle_event(event),
         }
     }
 
     pub fn statuses(&self) -> Vec<bool> {
         match self {
             Self::Condition(target, _) => vec![target.score >= target.target],
+            Self::Sequence(sequence) => sequence.statuses(),
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        match self {
+            Self::Condition(_, _) => false,
+            Self::Sequence(sequence) => sequence.failed,
+        }
+    }
+
+    pub fn get_attack_table(&self) -> Option<AttackTable> {
+        match self {
+            Self::Condition(_, scorer) => scorer.get_attack_table(),
+            Self::Sequence(sequence) =>
+                sequence.steps.iter().find_map(|s| s.get_attack_table()),
         }
     }
 }
@@ -117,13 +175,15 @@ impl SerializeUrlSafe for ChainConditions {
         match self {
             Self::Condition(target, scorer) => 
                 format! {"C{}{}", target.serialize(), scorer.serialize()},
+            Self::Sequence(sequence) => format! {"S{}", sequence.serialize()},
         }
     }
 
     fn deserialize(input: &mut crate::serialize::DeserializeInput) -> Result<Self, crate::serialize::DeserializeError> {
         Ok(match input.next()? {
             'C' => Self::Condition(ScoreTarget::deserialize(input)?, ChainScorer::deserialize(input)?),
-            _ => return Err(DeserializeError::new("ChainConditions type should be represented by C."),
+            'S' => Self::Sequence(Sequence::deserialize(input)?),
+            _ => return Err(DeserializeError::new("ChainConditions type should be represented by C or S."),
             )
         })
     }
@@ -151,9 +211,24 @@ pub enum ChainScorer {
         count_zone_damage: bool,
     },
     JeapordyDealt,
+    Attack(AttackTable),
+    Points,
 }
 
 impl ChainScorer {
+    // The table damage is counted with, if this counts damage
+    pub fn get_attack_table(&self) -> Option<AttackTable> {
+        match self {
+            Self::DamageDealt {count_zone_damage} =>
+                Some(AttackTable::tec(*count_zone_damage)),
+            Self::JeapordyDealt => Some(AttackTable::jeapordy()),
+            Self::Attack(table) => Some(table.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl Scorer<ChainEvent<'_>> for ChainScorer {
     fn score_event(&self, event: &ChainEvent) -> usize {
         match self {
             Self::LineClear{req_lines, req_piece, req_all_clear,
@@ -192,94 +267,15 @@ impl ChainScorer {
                 if let ChainEvent::LineClear(clear) = event {
                     return clear.clear.clear.lines;
                 }
-            Self::DamageDealt {count_zone_damage}=> {
-                match event {
-                    ChainEvent::LineClear(chain_clear) => {
-                        let spin_clear = chain_clear.clear;
-                        let clear = spin_clear.clear;
-                        if clear.lines == 0 {
-                            return 0;
-                        }
-                        if AllClearType::from_line_clear(&clear) == AllClearType::ALL_CLEAR {
-                            return 10;
-                        }
-                        let mut damage = match spin_clear.spin {
-                            Some(SpinType::Full) => clear.lines * 2,
-                            _ => match clear.lines {
-                                1 => 0,
-                                2 => 1,
-                                3 => 2,
-                                _ => 4,
-                            }
-                        };
-                        if chain_clear.b2b {
-                            damage += 1;
-                        };
-                        let combo_damage = if clear.in_zone && *count_zone_damage {
-                            match chain_clear.combo {
-                                0..=2 => 0,
-                                3..=4 => 1,
-                                5..=10 => 2,
-                                _ => 3,
-                            }
-                        } else {
-                            match chain_clear.combo {
-                                0..=2 => 0,
-                                3..=4 => 1,
-                                5..=7 => 2,
-                                8..=13 => 3,
-                                _ => 4,
-                            }
-                        };
-                        return damage + combo_damage;
-                    }
-                    ChainEvent::ZoneClear(lines) =>
-                        if *count_zone_damage {
-                            return match lines {
-                                20 => 17 + 17,
-                                21 => 18 + 18 + 10,
-                                22 => 20 + 20 + 12 + 10,
-                                23 => 20 + 20 + 20 + 20,
-                                _ => lines + lines / 2,
-                            }
-                        }
-                }
-            }
-            Self::JeapordyDealt => {
+            Self::DamageDealt {count_zone_damage} =>
+                return AttackTable::tec(*count_zone_damage).attack(event),
+            Self::JeapordyDealt =>
+                return AttackTable::jeapordy().attack(event),
+            Self::Attack(table) => return table.attack(event),
+            Self::Points =>
                 if let ChainEvent::LineClear(chain_clear) = event {
-                    let spin_clear = chain_clear.clear;
-                    let clear = spin_clear.clear;
-                    if clear.lines == 0 {
-                        return 0;
-                    }
-                    if AllClearType::from_line_clear(&clear) == AllClearType::ALL_CLEAR {
-                        return 8;
-                    }
-                    let mut damage = match spin_clear.spin {
-                        Some(SpinType::Full) => clear.lines * 2,
-                        Some(SpinType::Mini) => clear.lines * 2 - 1,
-                        _ => match clear.lines {
-                            1 => 0,
-                            2 => 1,
-                            3 => 2,
-                            _ => 4,
-                        }
-                    };
-                    if chain_clear.b2b {
-                        damage += 1;
-                    };
-                    let combo_damage = match chain_clear.combo {
-                        0..=2 => 0,
-                        3..=4 => 1,
-                        5 => 2,
-                        6 => 3,
-                        7 => 4,
-                        8 => 3,
-                        _ => 2,
-                    };
-                    return damage.max(combo_damage);
+                    return scoring::clear_points(chain_clear, 1);
                 }
-            }
         }
         0
     }
@@ -298,6 +294,8 @@ impl SerializeUrlSafe for ChainScorer {
             Self::LinesCleared => "L".to_owned(),
             Self::DamageDealt { count_zone_damage } => format! {"D{}", count_zone_damage.serialize()},
             Self::JeapordyDealt => "J".to_owned(),
+            Self::Attack(table) => format! {"A{}", table.serialize()},
+            Self::Points => "P".to_owned(),
         }
     }
 
@@ -317,7 +315,9 @@ impl SerializeUrlSafe for ChainScorer {
             'L' => Self::LinesCleared,
             'D' => Self::DamageDealt { count_zone_damage: bool::deserialize(input)? },
             'J' => Self::JeapordyDealt,
-            _ => return Err(DeserializeError::new("ChainScorer type should be represented by C, Z, L, D, or J.")),
+            'A' => Self::Attack(AttackTable::deserialize(input)?),
+            'P' => Self::Points,
+            _ => return Err(DeserializeError::new("ChainScorer type should be represented by C, Z, L, D, J, A, or P.")),
         })
     }
 }
\ No newline at end of file
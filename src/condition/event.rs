@@ -1,6 +1,14 @@
+use crate::condition::attack::AttackTable;
 use crate::condition::all_clear::AllClearType;
+use crate::condition::board_state::BoardCondition;
+use crate::condition::sequence::Scorer;
+use crate::condition::sequence::Sequence;
+use crate::condition::sequence::StepEvent;
 use crate::condition::spin::SpinConditions;
 use crate::condition::spin::SpinHandler;
+use crate::condition::spin::SpinRules;
+use crate::game::Action;
+use crate::game::BoardMask;
 use crate::game::PieceType;
 use crate::game::game::Event;
 use crate::serialize::DeserializeError;
@@ -67,15 +75,41 @@ pub struct Conditions {
 }
 
 impl Conditions {
-    pub fn handle_event(&mut self, event: &Event) {
+    pub fn handle_event(&mut self, event: &Event, rules: &SpinRules) {
         for condition in self.conditions.iter_mut() {
-            condition.handle_event(event);
+            condition.handle_event(event, rules);
         }
     }
 
     pub fn statuses(&self) -> Vec<bool> {
         self.conditions.iter().flat_map(|c| c.statuses()).collect()
     }
+
+    pub fn failed(&self) -> bool {
+        self.conditions.iter().any(|c| c.failed())
+    }
+
+    pub fn get_mask(&self) -> Option<&BoardMask> {
+        self.conditions.iter().find_map(|c| c.get_mask())
+    }
+
+    // The first attack table any condition counts damage with
+    pub fn get_attack_table(&self) -> Option<AttackTable> {
+        self.conditions.iter().find_map(|c| c.get_attack_table())
+    }
+
+    pub fn get_mask_mut(&mut self) -> Option<&mut BoardMask> {
+        self.conditions.iter_mut().find_map(|c| c.get_mask_mut())
+    }
+
+    // Whether any condition looks at spins, which depend on the kick used
+    pub fn has_spin_context(&self) -> bool {
+        self.conditions.iter().any(|c| c.has_spin_context())
+    }
+
+    pub fn get_masks_mut(&mut self) -> Vec<&mut BoardMask> {
+        self.conditions.iter_mut().flat_map(|c| c.get_masks_mut()).collect()
+    }
 }
 
 impl SerializeUrlSafe for Conditions {
@@ -90,13 +124,24 @@ impl SerializeUrlSafe for Conditions {
 
 #[derive(Hash, Eq, PartialEq, Clone)]
 pub enum EventConditions {
+    // Spins always follow the 3-corner T-Spin rules, whatever the puzzle's
+    // rules are, so older links keep scoring the way they were made
     TSpinContext(SpinHandler, Vec<SpinConditions>),
+    // Spins always follow the immobile all-spin rules
     AllSpinContext(SpinHandler, Vec<SpinConditions>),
+    // Spins follow the puzzle's rules
+    RulesSpinContext(SpinHandler, Vec<SpinConditions>),
     Condition(ScoreTarget, EventScorer),
+    All(Vec<EventConditions>),
+    Any(Vec<EventConditions>),
+    Not(Box<EventConditions>),
+    AtLeast(usize, Vec<EventConditions>),
+    Sequence(Sequence<EventScorer>),
+    Board(Box<BoardCondition>),
 }
 
 impl EventConditions {
-    pub fn handle_event(&mut self, event: &Event) {
+    pub fn handle_event(&mut self, event: &Event, rules: &SpinRules) {
         match self {
             Self::TSpinContext(handler, conditions) =>
                 if let Some(spin_event) = handler.handle_t_spin(event) {
@@ -110,8 +155,24 @@ impl EventConditions {
                         condition.handle_event(&spin_event);
                     }
                 }
-            Self::Condition(target, scorer) =>
-                target.score += scorer.score_event(event),
+            Self::RulesSpinContext(handler, conditions) =>
+                if let Some(spin_event) = handler.handle_spin(event, rules) {
+                    for condition in conditions.iter_mut() {
+                        condition.handle_event(&spin_event);
+                    }
+                }
+            Self::Condition(target, scorer) => {
+                target.score += scorer.score_event(event);
+                scorer.follow(event);
+            },
+            Self::All(conditions) | Self::Any(conditions)
+                | Self::AtLeast(_, conditions) =>
+                for condition in conditions.iter_mut() {
+                    condition.handle_event(event, rules);
+                }
+            Self::Not(condition) => condition.handle_event(event, rules),
+            Self::Sequence(sequence) => sequence.handle_event(event),
+            Self::Board(condition) => condition.handle_event(event),
         }
     }
 
@@ -121,7 +182,102 @@ impl EventConditions {
                 conditions.iter().flat_map(|c| c.statuses()).collect(),
             Self::AllSpinContext(_, conditions) =>
                 conditions.iter().flat_map(|c| c.statuses()).collect(),
+            Self::RulesSpinContext(_, conditions) =>
+                conditions.iter().flat_map(|c| c.statuses()).collect(),
             Self::Condition(target, _) => vec![target.score >= target.target],
+            Self::All(conditions) =>
+                vec![conditions.iter().all(|c| c.is_met())],
+            Self::Any(conditions) =>
+                vec![conditions.iter().any(|c| c.is_met())],
+            Self::Not(condition) => vec![!condition.is_met()],
+            Self::AtLeast(count, conditions) =>
+                vec![conditions.iter().filter(|c| c.is_met()).count() >= *count],
+            Self::Sequence(sequence) => sequence.statuses(),
+            Self::Board(condition) => condition.statuses(),
+        }
+    }
+
+    pub fn is_met(&self) -> bool {
+        self.statuses().iter().all(|s| *s)
+    }
+
+    pub fn failed(&self) -> bool {
+        match self {
+            Self::TSpinContext(_, conditions) =>
+                conditions.iter().any(|c| c.failed()),
+            Self::AllSpinContext(_, conditions) =>
+                conditions.iter().any(|c| c.failed()),
+            Self::RulesSpinContext(_, conditions) =>
+                conditions.iter().any(|c| c.failed()),
+            Self::Condition(_, _) => false,
+            Self::All(conditions) => conditions.iter().any(|c| c.failed()),
+            Self::Any(conditions) => !conditions.is_empty()
+                && conditions.iter().all(|c| c.failed()),
+            Self::Not(_) => false,
+            Self::AtLeast(count, conditions) =>
+                conditions.iter().filter(|c| !c.failed()).count() < *count,
+            Self::Sequence(sequence) => sequence.failed,
+            Self::Board(_) => false,
+        }
+    }
+
+    pub fn has_spin_context(&self) -> bool {
+        match self {
+            Self::TSpinContext(_, _) | Self::AllSpinContext(_, _)
+                | Self::RulesSpinContext(_, _) => true,
+            Self::All(conditions) | Self::Any(conditions)
+                | Self::AtLeast(_, conditions) =>
+                conditions.iter().any(|c| c.has_spin_context()),
+            Self::Not(condition) => condition.has_spin_context(),
+            _ => false,
+        }
+    }
+
+    pub fn get_attack_table(&self) -> Option<AttackTable> {
+        match self {
+            Self::TSpinContext(_, conditions)
+                | Self::AllSpinContext(_, conditions)
+                | Self::RulesSpinContext(_, conditions) =>
+                conditions.iter().find_map(|c| c.get_attack_table()),
+            Self::All(conditions) | Self::Any(conditions)
+                | Self::AtLeast(_, conditions) =>
+                conditions.iter().find_map(|c| c.get_attack_table()),
+            Self::Not(condition) => condition.get_attack_table(),
+            _ => None,
+        }
+    }
+
+    pub fn get_mask(&self) -> Option<&BoardMask> {
+        match self {
+            Self::All(conditions) | Self::Any(conditions)
+                | Self::AtLeast(_, conditions) =>
+                conditions.iter().find_map(|c| c.get_mask()),
+            Self::Not(condition) => condition.get_mask(),
+            Self::Board(condition) => condition.goal.get_mask(),
+            _ => None,
+        }
+    }
+
+    pub fn get_mask_mut(&mut self) -> Option<&mut BoardMask> {
+        match self {
+            Self::All(conditions) | Self::Any(conditions)
+                | Self::AtLeast(_, conditions) =>
+                conditions.iter_mut().find_map(|c| c.get_mask_mut()),
+            Self::Not(condition) => condition.get_mask_mut(),
+            Self::Board(condition) => condition.goal.get_mask_mut(),
+            _ => None,
+        }
+    }
+
+    pub fn get_masks_mut(&mut self) -> Vec<&mut BoardMask> {
+        match self {
+            Self::All(conditions) | Self::Any(conditions)
+                | Self::AtLeast(_, conditions) =>
+                conditions.iter_mut().flat_map(|c| c.get_masks_mut()).collect(),
+            Self::Not(condition) => condition.get_masks_mut(),
+            Self::Board(condition) =>
+                condition.goal.get_mask_mut().into_iter().collect(),
+            _ => Vec::new(),
         }
     }
 }
@@ -133,8 +289,17 @@ impl SerializeUrlSafe for EventConditions {
                 format! {"T{}{}", handler.serialize(), conditions.serialize()},
             Self::AllSpinContext(handler, conditions) =>
                 format! {"A{}{}", handler.serialize(), conditions.serialize()},
+            Self::RulesSpinContext(handler, conditions) =>
+                format! {"R{}{}", handler.serialize(), conditions.serialize()},
             Self::Condition(target, scorer) => 
                 format! {"C{}{}", target.serialize(), scorer.serialize()},
+            Self::All(conditions) => format! {"E{}", conditions.serialize()},
+            Self::Any(conditions) => format! {"Y{}", conditions.serialize()},
+            Self::Not(condition) => format! {"N{}", condition.serialize()},
+            Self::AtLeast(count, conditions) =>
+                format! {"K{}{}", count.serialize(), conditions.serialize()},
+            Self::Sequence(sequence) => format! {"S{}", sequence.serialize()},
+            Self::Board(condition) => format! {"B{}", condition.serialize()},
         }
     }
 
@@ -142,8 +307,15 @@ impl SerializeUrlSafe for EventConditions {
         Ok(match input.next()? {
             'T' => Self::TSpinContext(SpinHandler::deserialize(input)?, Vec::deserialize(input)?),
             'A' => Self::AllSpinContext(SpinHandler::deserialize(input)?, Vec::deserialize(input)?),
+            'R' => Self::RulesSpinContext(SpinHandler::deserialize(input)?, Vec::deserialize(input)?),
             'C' => Self::Condition(ScoreTarget::deserialize(input)?, EventScorer::deserialize(input)?),
-            _ => return Err(DeserializeError::new("EventConditions type should be represented by T, A, or C."),
+            'E' => Self::All(Vec::deserialize(input)?),
+            'Y' => Self::Any(Vec::deserialize(input)?),
+            'N' => Self::Not(Box::new(EventConditions::deserialize(input)?)),
+            'K' => Self::AtLeast(usize::deserialize(input)?, Vec::deserialize(input)?),
+            'S' => Self::Sequence(Sequence::deserialize(input)?),
+            'B' => Self::Board(Box::new(BoardCondition::deserialize(input)?)),
+            _ => return Err(DeserializeError::new("EventConditions type should be represented by T, A, R, C, E, Y, N, K, S, or B."),
             )
         })
     }
@@ -163,9 +335,39 @@ pub enum EventScorer {
     },
     // Count totals, sometimes increasing by more than one
     LinesCleared,
+    // Count resources used
+    PiecesPlaced,
+    HoldsUsed,
+    ZonesActivated,
+    Rotations,
+    // Soft dropping sends a move for every cell, so it only counts once
+    // until some other input
+    Inputs {
+        soft_dropping: bool,
+    },
 }
 
 impl EventScorer {
+    // Keeps what later events are scored by
+    fn follow(&mut self, event: &Event) {
+        if let (Self::Inputs { soft_dropping }, Event::Input(action))
+                = (self, event) {
+            *soft_dropping = matches!(action, Action::MoveDown);
+        }
+    }
+}
+
+impl StepEvent for Event {
+    fn is_clear(&self) -> bool {
+        match self {
+            Event::LineClear(clear) => clear.lines > 0,
+            Event::ZoneClear(lines) => *lines > 0,
+            _ => false,
+        }
+    }
+}
+
+impl Scorer<Event> for EventScorer {
     fn score_event(&self, event: &Event) -> usize {
         match self {
             Self::LineClear{req_lines, req_piece, req_all_clear,
@@ -193,6 +395,27 @@ impl EventScorer {
                 if let Event::LineClear(clear) = event {
                     return clear.lines;
                 }
+            Self::PiecesPlaced =>
+                if let Event::Place(_) = event {
+                    return 1;
+                }
+            Self::HoldsUsed =>
+                if let Event::Hold = event {
+                    return 1;
+                }
+            Self::ZonesActivated =>
+                if let Event::ZoneStart = event {
+                    return 1;
+                }
+            Self::Rotations =>
+                if let Event::Rotate(_) = event {
+                    return 1;
+                }
+            Self::Inputs { soft_dropping } => match event {
+                Event::Input(Action::MoveDown) if *soft_dropping => (),
+                Event::Input(_) => return 1,
+                _ => (),
+            },
         }
         0
     }
@@ -205,6 +428,12 @@ impl SerializeUrlSafe for EventScorer {
                 format! {"C{}{}{}{}", req_lines.serialize(), req_piece.serialize(), req_all_clear.serialize(), negate.serialize()},
             Self::ZoneClear { req_lines } => format!("Z{}", req_lines.serialize()),
             Self::LinesCleared => "L".to_owned(),
+            Self::PiecesPlaced => "P".to_owned(),
+            Self::HoldsUsed => "H".to_owned(),
+            Self::ZonesActivated => "A".to_owned(),
+            Self::Rotations => "R".to_owned(),
+            Self::Inputs { soft_dropping } =>
+                format! {"I{}", soft_dropping.serialize()},
         }
     }
 
@@ -218,7 +447,45 @@ impl SerializeUrlSafe for EventScorer {
             },
             'Z' => Self::ZoneClear { req_lines: ReqOrMin::deserialize(input)? },
             'L' => Self::LinesCleared,
-            _ => return Err(DeserializeError::new("EventScorer type should be represented by C, Z, or L.")),
+            'P' => Self::PiecesPlaced,
+            'H' => Self::HoldsUsed,
+            'A' => Self::ZonesActivated,
+            'R' => Self::Rotations,
+            'I' => Self::Inputs { soft_dropping: bool::deserialize(input)? },
+            _ => return Err(DeserializeError::new("EventScorer type should be represented by C, Z, L, P, H, A, R, or I.")),
         })
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::Vector;
+
+    fn inputs_counted(events: &[Event]) -> usize {
+        let mut condition = EventConditions::Condition(
+            ScoreTarget { score: 0, target: 0 },
+            EventScorer::Inputs { soft_dropping: false });
+        for event in events {
+            condition.handle_event(event, &SpinRules::default());
+        }
+        match condition {
+            EventConditions::Condition(target, _) => target.score,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn soft_drop_counts_once() {
+        let soft_drop = [Event::Input(Action::MoveDown), Event::Move(Vector::ONE_DOWN)];
+        let events: Vec<Event> = soft_drop.iter().cycle().take(6).cloned()
+            .chain([Event::Input(Action::PlacePiece)])
+            .collect();
+        assert_eq!(inputs_counted(&events), 2);
+    }
+
+    #[test]
+    fn taps_count_each() {
+        let events = [Event::Input(Action::MoveLeft), Event::Input(Action::MoveLeft)];
+        assert_eq!(inputs_counted(&events), 2);
+    }
+}
@@ -0,0 +1,478 @@
+use std::fmt;
+
+use crate::condition::all_clear::AllClearType;
+use crate::condition::attack::AttackTable;
+use crate::condition::board_state::BoardCondition;
+use crate::condition::board_state::BoardGoal;
+use crate::condition::chain::ChainConditions;
+use crate::condition::chain::ChainScorer;
+use crate::condition::event::Conditions;
+use crate::condition::event::EventConditions;
+use crate::condition::event::EventScorer;
+use crate::condition::event::ReqOrMin;
+use crate::condition::event::ScoreTarget;
+use crate::condition::sequence::Sequence;
+use crate::condition::spin::SpinConditions;
+use crate::condition::spin::SpinRules;
+use crate::condition::spin::SpinScorer;
+use crate::condition::spin::SpinType;
+use crate::game::PieceType;
+use crate::serialize::SerializeUrlSafe;
+
+pub fn zone_name(lines: usize) -> String {
+    match lines {
+        5 => "Pentrix".to_owned(),
+        6 => "Hextrix".to_owned(),
+        7 => "Septrix".to_owned(),
+        8 => "Octorix".to_owned(),
+        9 => "Pendecatrix".to_owned(),
+        10 => "Decatrix".to_owned(),
+        11 => "Undecatrix".to_owned(),
+        12 => "Dodecatrix".to_owned(),
+        13 => "Tridecatrix".to_owned(),
+        14 => "Quadecatrix".to_owned(),
+        15 => "Decapentrix".to_owned(),
+        16 => "Decahextrix".to_owned(),
+        17 => "Decaseptrix".to_owned(),
+        18 => "Perfectrix".to_owned(),
+        19 => "Penultimatrix".to_owned(),
+        20 => "Ultimatrix".to_owned(),
+        21 => "Kirbtrix".to_owned(),
+        22 => "Impossitrix".to_owned(),
+        23 => "Infinitrix".to_owned(),
+        24 => "Electrix".to_owned(),
+        25 => "Electrix+".to_owned(),
+        26 => "Electrix++".to_owned(),
+        l => format!{"{}-Trix", l},
+    }
+}
+
+pub fn spin_rules_name(rules: &SpinRules) -> &'static str {
+    match rules {
+        SpinRules::ThreeCorner { tst_upgrade: true } => "T-Spins",
+        SpinRules::ThreeCorner { tst_upgrade: false } => "T-Spins, No TST Upgrade",
+        SpinRules::Immobile => "All-Spins",
+        SpinRules::AllMini => "All-Mini",
+        SpinRules::NoSpins => "No Spins",
+    }
+}
+
+pub fn attack_name(table: &AttackTable) -> &'static str {
+    match table {
+        t if *t == AttackTable::tetrio() => "TETR.IO Attack",
+        t if *t == AttackTable::puyo_puyo_tetris() => "PPT Attack",
+        t if *t == AttackTable::jstris() => "Jstris Attack",
+        t if *t == AttackTable::tec(true) => "Zone Damage",
+        t if *t == AttackTable::tec(false) => "Damage",
+        t if *t == AttackTable::jeapordy() => "Jeapordy",
+        _ => "Attack",
+    }
+}
+
+#[derive(Eq, PartialEq, Clone)]
+pub struct LineClearReqs {
+    pub negate: bool,
+    pub req_lines: ReqOrMin,
+    pub req_piece: Option<PieceType>,
+    pub req_all_clear: AllClearType,
+    pub req_spin: Option<Option<SpinType>>,
+    pub req_hard: Option<bool>,
+    pub req_b2b: Option<bool>,
+    pub req_combo: ReqOrMin,
+}
+
+impl fmt::Display for LineClearReqs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write! {f,
+            "{}{}{}{}{}{}{}{}{}{}{}s",
+            match self.negate {
+                true => "Non ",
+                false => "",
+            },
+            match self.req_combo {
+                ReqOrMin::Req(c) => format! {"{}C ", c},
+                ReqOrMin::Min(0) => "".to_owned(),
+                ReqOrMin::Min(c) => format! {">{}C ", c},
+            },
+            match self.req_b2b {
+                None => "",
+                Some(false) => "Non-B2B ",
+                Some(true) => "B2B ",
+            },
+            match self.req_hard {
+                None => "",
+                Some(false) => "Non-Hard ",
+                Some(true) => "Hard "
+            },
+            match self.req_spin {
+                None => "",
+                Some(None) => "",
+                Some(Some(SpinType::Full)) => "Full ",
+                Some(Some(SpinType::Mini)) => "Mini ",
+            },
+            match self.req_piece {
+                None => "".to_owned(),
+                Some(p) => p.serialize(),
+            },
+            match self.req_piece {
+                None => "",
+                Some(_) => match self.req_spin {
+                    None => " ",
+                    Some(_) => "-"
+                }
+            },
+            match self.req_spin {
+                None => "",
+                Some(_) => "Spin "
+            },
+            match self.req_lines {
+                ReqOrMin::Req(0) => "Zero".to_owned(),
+                ReqOrMin::Req(1) => "Single".to_owned(),
+                ReqOrMin::Req(2) => "Double".to_owned(),
+                ReqOrMin::Req(3) => "Triple".to_owned(),
+                ReqOrMin::Req(4) => "Quad".to_owned(),
+                ReqOrMin::Req(n) => format! {"{}-Clear", n},
+                ReqOrMin::Min(0) => "Placement".to_owned(),
+                ReqOrMin::Min(1) => match self.req_all_clear {
+                    AllClearType::NONE => "Clear".to_owned(),
+                    _ => "".to_owned(),
+                }
+                ReqOrMin::Min(2) => "Double+".to_owned(),
+                ReqOrMin::Min(3) => "Triple+".to_owned(),
+                ReqOrMin::Min(4) => "Quad+".to_owned(),
+                ReqOrMin::Min(n) => format! {"{}-Clear+", n},
+            },
+            match self.req_all_clear {
+                AllClearType::NONE => "",
+                _ => match self.req_lines {
+                    ReqOrMin::Min(1) => "",
+                    _ => " ",
+                }
+            },
+            match self.req_all_clear {
+                AllClearType::NONE => "",
+                AllClearType::GRAY_CLEAR => "Gray-Clear",
+                AllClearType::COLOR_CLEAR => "Color-Clear",
+                AllClearType::ALL_CLEAR => "All-Clear",
+            }
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Clone)]
+pub enum Description {
+    TSpinContext,
+    AllSpinContext,
+    RulesSpinContext,
+    ChainContext { b2b: bool, combo: usize },
+    ZoneChainContext { b2b: bool, combo: usize },
+    All,
+    Any,
+    Not,
+    AtLeast(usize),
+    Sequence { consecutive: bool },
+    LineClear(LineClearReqs),
+    ZoneClear(ReqOrMin),
+    LinesCleared,
+    PiecesPlaced,
+    HoldsUsed,
+    ZonesActivated,
+    Rotations,
+    Inputs,
+    Attack(&'static str),
+    Points,
+    Board { goal: BoardGoalKind, negate: bool },
+}
+
+#[derive(Eq, PartialEq, Copy, Clone)]
+pub enum BoardGoalKind {
+    MatchBoard,
+    FillCells,
+    Residue,
+    MaxHeight(usize),
+    NoHoles,
+}
+
+impl fmt::Display for Description {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TSpinContext => write!(f, "Spins: T Only (3-Corner)"),
+            Self::AllSpinContext => write!(f, "Spins: All (Immobile)"),
+            Self::RulesSpinContext => write!(f, "Spins: Puzzle Rules"),
+            Self::ChainContext { b2b, combo } | Self::ZoneChainContext { b2b, combo } =>
+                write! {f, "B2B: {}, Combo: {}",
+                    match b2b {
+                        true => "On",
+                        false => "Off",
+                    }, combo},
+            Self::All => write!(f, "All Of:"),
+            Self::Any => write!(f, "Any Of:"),
+            Self::Not => write!(f, "Not:"),
+            Self::AtLeast(count) => write!(f, "At Least {} Of:", count),
+            Self::Sequence { consecutive } => write!(f, "{}", match consecutive {
+                true => "Consecutive Sequence",
+                false => "Sequence",
+            }),
+            Self::LineClear(reqs) => write!(f, "{}", reqs),
+            Self::ZoneClear(req_lines) => write!(f, "{}", match req_lines {
+                ReqOrMin::Min(0) => "Zones".to_owned(),
+                ReqOrMin::Min(l) => zone_name(*l),
+                ReqOrMin::Req(l) => format! {"={}", zone_name(*l)},
+            }),
+            Self::LinesCleared => write!(f, "Lines"),
+            Self::PiecesPlaced => write!(f, "Pieces"),
+            Self::HoldsUsed => write!(f, "Holds"),
+            Self::ZonesActivated => write!(f, "Zones Used"),
+            Self::Rotations => write!(f, "Rotations"),
+            Self::Inputs => write!(f, "Inputs"),
+            Self::Attack(name) => write!(f, "{}", name),
+            Self::Points => write!(f, "Points"),
+            Self::Board { goal, negate } => write! {f, "{}{}",
+                match negate {
+                    true => "Not ",
+                    false => "",
+                },
+                match goal {
+                    BoardGoalKind::MatchBoard => "Match Target Board".to_owned(),
+                    BoardGoalKind::FillCells => "Fill Target Cells".to_owned(),
+                    BoardGoalKind::Residue => "Leave Target Residue".to_owned(),
+                    BoardGoalKind::MaxHeight(h) => format! {"Height {} or Less", h},
+                    BoardGoalKind::NoHoles => "No Holes".to_owned(),
+                }},
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Clone)]
+pub struct Progress {
+    pub description: Description,
+    pub score: usize,
+    pub target: usize,
+    pub met: bool,
+    pub failed: bool,
+    pub children: Vec<Progress>,
+}
+
+impl Progress {
+    fn node(description: Description, met: bool, failed: bool,
+            children: Vec<Progress>) -> Self {
+        let score = children.iter().filter(|c| c.met).count();
+        Self {
+            description,
+            score,
+            target: children.len(),
+            met,
+            failed,
+            children,
+        }
+    }
+
+    fn leaf(description: Description, target: &ScoreTarget) -> Self {
+        Self {
+            description,
+            score: target.score,
+            target: target.target,
+            met: target.score >= target.target,
+            failed: false,
+            children: Vec::new(),
+        }
+    }
+
+    fn sequence<S, F>(sequence: &Sequence<S>, describe: F) -> Self
+    where   F: Fn(&S) -> Description {
+        Self {
+            description: Description::Sequence {
+                consecutive: sequence.consecutive },
+            score: sequence.progress,
+            target: sequence.steps.len(),
+            met: sequence.statuses()[0],
+            failed: sequence.failed,
+            children: sequence.steps.iter().enumerate().map(|(i, s)| Self {
+                description: describe(s),
+                score: (i < sequence.progress) as usize,
+                target: 1,
+                met: i < sequence.progress,
+                failed: false,
+                children: Vec::new(),
+            }).collect(),
+        }
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty() && !matches!(self.description,
+            Description::TSpinContext | Description::AllSpinContext
+            | Description::RulesSpinContext
+            | Description::ChainContext {..} | Description::ZoneChainContext {..}
+            | Description::All | Description::Any | Description::Not
+            | Description::AtLeast(_) | Description::Sequence {..})
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.description {
+            Description::Board {..} => write!(f, "{}", self.description),
+            _ if self.is_leaf() || matches!(self.description, Description::Sequence {..}) =>
+                write!(f, "{}: {}/{}", self.description, self.score, self.target),
+            _ => write!(f, "{}", self.description),
+        }
+    }
+}
+
+impl EventScorer {
+    pub fn describe(&self) -> Description {
+        match self.clone() {
+            Self::LineClear { req_lines, req_piece, req_all_clear, negate } =>
+                Description::LineClear(LineClearReqs {
+                    negate, req_lines, req_piece, req_all_clear,
+                    req_spin: None,
+                    req_hard: None,
+                    req_b2b: None,
+                    req_combo: ReqOrMin::Min(0),
+                }),
+            Self::ZoneClear { req_lines } => Description::ZoneClear(req_lines),
+            Self::LinesCleared => Description::LinesCleared,
+            Self::PiecesPlaced => Description::PiecesPlaced,
+            Self::HoldsUsed => Description::HoldsUsed,
+            Self::ZonesActivated => Description::ZonesActivated,
+            Self::Rotations => Description::Rotations,
+            Self::Inputs { .. } => Description::Inputs,
+        }
+    }
+}
+
+impl SpinScorer {
+    pub fn describe(&self) -> Description {
+        match self.clone() {
+            Self::LineClear { req_lines, req_piece, req_all_clear,
+                req_spin, req_hard, negate } =>
+                Description::LineClear(LineClearReqs {
+                    negate, req_lines, req_piece, req_all_clear,
+                    req_spin, req_hard,
+                    req_b2b: None,
+                    req_combo: ReqOrMin::Min(0),
+                }),
+            Self::ZoneClear { req_lines } => Description::ZoneClear(req_lines),
+            Self::LinesCleared => Description::LinesCleared,
+        }
+    }
+}
+
+impl ChainScorer {
+    pub fn describe(&self) -> Description {
+        match self.clone() {
+            Self::LineClear { req_lines, req_piece, req_all_clear,
+                req_spin, req_hard, req_b2b, req_combo, negate } =>
+                Description::LineClear(LineClearReqs {
+                    negate, req_lines, req_piece, req_all_clear,
+                    req_spin, req_hard, req_b2b, req_combo,
+                }),
+            Self::ZoneClear { req_lines } => Description::ZoneClear(req_lines),
+            Self::LinesCleared => Description::LinesCleared,
+            Self::DamageDealt { count_zone_damage } =>
+                Description::Attack(attack_name(&AttackTable::tec(count_zone_damage))),
+            Self::JeapordyDealt => Description::Attack(attack_name(&AttackTable::jeapordy())),
+            Self::Attack(table) => Description::Attack(attack_name(&table)),
+            Self::Points => Description::Points,
+        }
+    }
+}
+
+impl BoardCondition {
+    pub fn describe(&self) -> Description {
+        Description::Board {
+            goal: match self.goal {
+                BoardGoal::MatchBoard(_) => BoardGoalKind::MatchBoard,
+                BoardGoal::FillCells(_) => BoardGoalKind::FillCells,
+                BoardGoal::Residue(_) => BoardGoalKind::Residue,
+                BoardGoal::MaxHeight(h) => BoardGoalKind::MaxHeight(h),
+                BoardGoal::NoHoles => BoardGoalKind::NoHoles,
+            },
+            negate: self.negate,
+        }
+    }
+}
+
+impl ChainConditions {
+    pub fn progress(&self) -> Progress {
+        match self {
+            Self::Condition(target, scorer) =>
+                Progress::leaf(scorer.describe(), target),
+            Self::Sequence(sequence) =>
+                Progress::sequence(sequence, |s| s.describe()),
+        }
+    }
+}
+
+impl SpinConditions {
+    pub fn progress(&self) -> Progress {
+        match self {
+            Self::ChainContext(handler, conditions) => Progress::node(
+                Description::ChainContext {
+                    b2b: handler.b2b, combo: handler.combo },
+                self.is_met(), self.failed(),
+                conditions.iter().map(|c| c.progress()).collect()),
+            Self::ZoneChainContext(handler, conditions) => Progress::node(
+                Description::ZoneChainContext {
+                    b2b: handler.b2b, combo: handler.combo },
+                self.is_met(), self.failed(),
+                conditions.iter().map(|c| c.progress()).collect()),
+            Self::Condition(target, scorer) =>
+                Progress::leaf(scorer.describe(), target),
+            Self::Sequence(sequence) =>
+                Progress::sequence(sequence, |s| s.describe()),
+        }
+    }
+
+    fn is_met(&self) -> bool {
+        self.statuses().iter().all(|s| *s)
+    }
+}
+
+impl EventConditions {
+    pub fn progress(&self) -> Progress {
+        let met = self.is_met();
+        let failed = self.failed();
+        match self {
+            Self::TSpinContext(_, conditions) => Progress::node(
+                Description::TSpinContext, met, failed,
+                conditions.iter().map(|c| c.progress()).collect()),
+            Self::AllSpinContext(_, conditions) => Progress::node(
+                Description::AllSpinContext, met, failed,
+                conditions.iter().map(|c| c.progress()).collect()),
+            Self::RulesSpinContext(_, conditions) => Progress::node(
+                Description::RulesSpinContext, met, failed,
+                conditions.iter().map(|c| c.progress()).collect()),
+            Self::Condition(target, scorer) =>
+                Progress::leaf(scorer.describe(), target),
+            Self::All(conditions) => Progress::node(
+                Description::All, met, failed,
+                conditions.iter().map(|c| c.progress()).collect()),
+            Self::Any(conditions) => Progress::node(
+                Description::Any, met, failed,
+                conditions.iter().map(|c| c.progress()).collect()),
+            Self::Not(condition) => Progress::node(
+                Description::Not, met, failed,
+                vec![condition.progress()]),
+            Self::AtLeast(count, conditions) => Progress::node(
+                Description::AtLeast(*count), met, failed,
+                conditions.iter().map(|c| c.progress()).collect()),
+            Self::Sequence(sequence) =>
+                Progress::sequence(sequence, |s| s.describe()),
+            Self::Board(condition) => Progress {
+                description: condition.describe(),
+                score: condition.met as usize,
+                target: 1,
+                met: condition.met,
+                failed: false,
+                children: Vec::new(),
+            },
+        }
+    }
+}
+
+impl Conditions {
+    pub fn progress(&self) -> Vec<Progress> {
+        self.conditions.iter().map(|c| c.progress()).collect()
+    }
+}
@@ -0,0 +1,110 @@
+use crate::serialize::DeserializeError;
+use crate::serialize::SerializeUrlSafe;
+
+pub trait Scorer<E> {
+    fn score_event(&self, event: &E) -> usize;
+}
+
+pub trait StepEvent {
+    fn is_clear(&self) -> bool;
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct Sequence<S> {
+    pub steps: Vec<S>,
+    pub progress: usize,
+    pub consecutive: bool,
+    pub failed: bool,
+}
+
+impl<S> Sequence<S> {
+    pub fn new(steps: Vec<S>, consecutive: bool) -> Self {
+        Self {
+            steps,
+            progress: 0,
+            consecutive,
+            failed: false,
+        }
+    }
+
+    pub fn handle_event<E>(&mut self, event: &E)
+    where   S: Scorer<E>,
+            E: StepEvent {
+        if self.failed || self.progress >= self.steps.len() {
+            return;
+        }
+        if self.steps[self.progress].score_event(event) > 0 {
+            self.progress += 1;
+            return;
+        }
+        // Skipping ahead to a later step breaks the order
+        let skipped = self.steps[self.progress + 1..].iter()
+            .any(|s| s.score_event(event) > 0);
+        let interrupted = self.consecutive && self.progress > 0
+            && event.is_clear();
+        if skipped || interrupted {
+            self.failed = true;
+        }
+    }
+
+    // An empty sequence, as the editor starts one, is never met
+    pub fn is_complete(&self) -> bool {
+        !self.steps.is_empty() && self.progress >= self.steps.len()
+    }
+
+    pub fn statuses(&self) -> Vec<bool> {
+        vec![self.is_complete() && !self.failed]
+    }
+}
+
+impl<S> SerializeUrlSafe for Sequence<S>
+where   S: SerializeUrlSafe {
+    fn serialize(&self) -> String {
+        format! {"{}{}{}{}",
+            self.steps.serialize(),
+            self.progress.serialize(),
+            self.consecutive.serialize(),
+            self.failed.serialize(),
+        }
+    }
+
+    fn deserialize(input: &mut crate::serialize::DeserializeInput) -> Result<Self, crate::serialize::DeserializeError> {
+        let sequence = Self {
+            steps: Vec::deserialize(input)?,
+            progress: usize::deserialize(input)?,
+            consecutive: bool::deserialize(input)?,
+            failed: bool::deserialize(input)?,
+        };
+        if sequence.progress > sequence.steps.len() {
+            return Err(DeserializeError::new("Sequence progress should not exceed its number of steps."));
+        }
+        Ok(sequence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct AnyClear;
+
+    impl StepEvent for bool {
+        fn is_clear(&self) -> bool {
+            *self
+        }
+    }
+
+    impl Scorer<bool> for AnyClear {
+        fn score_event(&self, event: &bool) -> usize {
+            *event as usize
+        }
+    }
+
+    #[test]
+    fn empty_sequence_is_never_met() {
+        let mut sequence: Sequence<AnyClear> = Sequence::new(Vec::new(), false);
+        assert!(!sequence.is_complete());
+        sequence.handle_event(&true);
+        assert_eq!(sequence.statuses(), vec![false]);
+    }
+}
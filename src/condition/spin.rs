@@ -1,10 +1,14 @@
 use core::ops::Add;
 
+use crate::condition::attack::AttackTable;
 use crate::condition::all_clear::AllClearType;
 use crate::condition::chain::ChainConditions;
 use crate::condition::chain::ChainHandler;
 use crate::condition::event::ReqOrMin;
 use crate::condition::event::ScoreTarget;
+use crate::condition::sequence::Scorer;
+use crate::condition::sequence::Sequence;
+use crate::condition::sequence::StepEvent;
 use crate::game::PieceType;
 use crate::game::game::Event;
 use crate::game::game::LineClear;
@@ -48,6 +52,113 @@ pub enum SpinEvent<'a> {
     ZoneClear(usize),
 }
 
+impl StepEvent for SpinEvent<'_> {
+    fn is_clear(&self) -> bool {
+        match self {
+            SpinEvent::LineClear(spin_clear) => spin_clear.clear.lines > 0,
+            SpinEvent::ZoneClear(lines) => *lines > 0,
+        }
+    }
+}
+
+#[derive(Hash, Eq, PartialEq, Copy, Clone)]
+pub enum SpinRules {
+    // T-Spins by the 3-corner rule, minis unless both front corners are
+    // filled or the rotation used the TST kick
+    ThreeCorner {
+        tst_upgrade: bool,
+    },
+    // Any piece that can't move after rotating spins, minis when kicked
+    // and clearing fewer lines than the piece is tall
+    Immobile,
+    // T-Spins by the 3-corner rule, other immobile pieces are always minis
+    AllMini,
+    NoSpins,
+}
+
+impl SpinRules {
+    pub fn detect(&self, clear: &LineClear, kick: usize) -> Option<SpinType> {
+        match self {
+            Self::ThreeCorner { tst_upgrade } =>
+                three_corner_spin(clear, kick, *tst_upgrade),
+            Self::Immobile => immobile_spin(clear, kick),
+            Self::AllMini => match clear.active.piece_type {
+                PieceType::T => three_corner_spin(clear, kick, true),
+                _ => immobile_spin(clear, kick).map(|_| SpinType::Mini),
+            },
+            Self::NoSpins => None,
+        }
+    }
+}
+
+impl Default for SpinRules {
+    fn default() -> Self {
+        Self::ThreeCorner { tst_upgrade: true }
+    }
+}
+
+impl SerializeUrlSafe for SpinRules {
+    fn serialize(&self) -> String {
+        match self {
+            Self::ThreeCorner { tst_upgrade } =>
+                format! {"T{}", tst_upgrade.serialize()},
+            Self::Immobile => "I".to_owned(),
+            Self::AllMini => "M".to_owned(),
+            Self::NoSpins => "N".to_owned(),
+        }
+    }
+
+    fn deserialize(input: &mut crate::serialize::DeserializeInput) -> Result<Self, crate::serialize::DeserializeError> {
+        Ok(match input.next()? {
+            'T' => Self::ThreeCorner { tst_upgrade: bool::deserialize(input)? },
+            'I' => Self::Immobile,
+            'M' => Self::AllMini,
+            'N' => Self::NoSpins,
+            _ => return Err(DeserializeError::new("SpinRules should be represented by T, I, M, or N.")),
+        })
+    }
+}
+
+fn three_corner_spin(clear: &LineClear, kick: usize, tst_upgrade: bool) -> Option<SpinType> {
+    if clear.active.piece_type != PieceType::T {
+        return None;
+    }
+    let back_corners = [Vector::new(-1, -1),
+        Vector::new(1, -1)].iter()
+        .map(|v| clear.active.pos.add(v.rotate(clear.active.rot)))
+        .filter(|p| clear.board[*p] != None).count();
+    let front_corners = [Vector::new(-1, 1),
+        Vector::new(1, 1)].iter()
+        .map(|v| clear.active.pos.add(v.rotate(clear.active.rot)))
+        .filter(|p| clear.board[*p] != None).count();
+    if back_corners + front_corners < 3 {
+        None
+    } else {
+        Some(match front_corners == 2 || (tst_upgrade && kick == 4) {
+            true => SpinType::Full,
+            false => SpinType::Mini,
+        })
+    }
+}
+
+fn immobile_spin(clear: &LineClear, kick: usize) -> Option<SpinType> {
+    if [Vector::ONE_UP, Vector::ONE_DOWN,
+        Vector::ONE_LEFT, Vector::ONE_RIGHT].iter()
+        .any(|v| clear.active.clone().try_move(&clear.board, *v)) {
+        return None;
+    }
+    let positions = clear.active.get_mino_positions();
+    let min = positions.iter().map(|p| p.y).min()
+        .unwrap_or(0);
+    let max = positions.iter().map(|p| p.y).max()
+        .unwrap_or(0);
+    let height = (max - min + 1) as usize;
+    Some(match clear.lines < height && kick > 0 {
+        false => SpinType::Full,
+        true => SpinType::Mini,
+    })
+}
+
 #[derive(Hash, Eq, PartialEq, Clone)]
 pub struct SpinHandler {
     last_kick: Option<usize>,
@@ -61,39 +172,18 @@ impl SpinHandler {
     fn handle_last_kick(&mut self, event: &Event) -> Option<usize> {
         match *event {
             Event::Spawn => self.last_kick = None,
-            Event::Move => self.last_kick = None,
+            Event::Move(_) => self.last_kick = None,
             Event::Rotate(kick) => self.last_kick = Some(kick),
             _ => (),
         }
         return self.last_kick;
     }
 
-    pub fn handle_t_spin<'a>(&mut self, event: &'a Event) -> Option<SpinEvent<'a>> {
+    pub fn handle_spin<'a>(&mut self, event: &'a Event, rules: &SpinRules) -> Option<SpinEvent<'a>> {
         let kick = self.handle_last_kick(event);
         match event {
             Event::LineClear(clear) => {
-                let spin = if clear.active.piece_type == PieceType::T {
-                    kick.and_then(|k| {
-                        let back_corners = [Vector::new(-1, -1),
-                            Vector::new(1, -1)].iter()
-                            .map(|v| clear.active.pos.add(v.rotate(clear.active.rot)))
-                            .filter(|p| clear.board[*p] != None).count();
-                        let front_corners = [Vector::new(-1, 1),
-                            Vector::new(1, 1)].iter()
-                            .map(|v| clear.active.pos.add(v.rotate(clear.active.rot)))
-                            .filter(|p| clear.board[*p] != None).count();
-                        if back_corners + front_corners < 3 {
-                            None
-                        } else {
-                            Some(match front_corners == 2 || k == 4 {
-                                true => SpinType::Full,
-                                false => SpinType::Mini,
-                            })
-                        }
-                    })
-                } else {
-                    None
-                };
+                let spin = kick.and_then(|k| rules.detect(clear, k));
                 Some(SpinEvent::LineClear(SpinClear {
                     clear: clear,
                     spin: spin.clone(),
@@ -105,37 +195,12 @@ impl SpinHandler {
         }
     }
 
+    pub fn handle_t_spin<'a>(&mut self, event: &'a Event) -> Option<SpinEvent<'a>> {
+        self.handle_spin(event, &SpinRules::ThreeCorner { tst_upgrade: true })
+    }
+
     pub fn handle_all_spin<'a>(&mut self, event: &'a Event) -> Option<SpinEvent<'a>> {
-        let kick = self.handle_last_kick(event);
-        match event {
-            Event::LineClear(clear) => {
-                let spin = kick.and_then(|k| {
-                    if [Vector::ONE_UP, Vector::ONE_DOWN,
-                        Vector::ONE_LEFT, Vector::ONE_RIGHT].iter()
-                        .any(|v| clear.active.clone().try_move(&clear.board, *v)) {
-                        None
-                    } else {
-                        let positions = clear.active.get_mino_positions();
-                        let min = positions.iter().map(|p| p.y).min()
-                            .unwrap_or(0);
-                        let max = positions.iter().map(|p| p.y).max()
-                            .unwrap_or(0);
-                        let height = (max - min + 1) as usize;
-                        Some(match clear.lines < height && k > 0 {
-                            false => SpinType::Full,
-                            true => SpinType::Mini,
-                        })
-                    }
-                });
-                Some(SpinEvent::LineClear(SpinClear {
-                    clear: clear,
-                    spin: spin.clone(),
-                    hard: clear.lines >= 4 || spin != None,
-                }))
-            }
-            Event::ZoneClear(lines) => Some(SpinEvent::ZoneClear(*lines)),
-            _ => None,
-        }
+        self.handle_spin(event, &SpinRules::Immobile)
     }
 }
 
@@ -158,6 +223,7 @@ pub enum SpinConditions {
     ChainContext(ChainHandler, Vec<ChainConditions>),
     ZoneChainContext(ChainHandler, Vec<ChainConditions>),
     Condition(ScoreTarget, SpinScorer),
+    Sequence(Sequence<SpinScorer>),
 }
 
 impl SpinConditions {
@@ -177,6 +243,7 @@ impl SpinConditions {
                 }
             Self::Condition(target, scorer) =>
                 target.score += scorer.score_event(event),
+            Self::Sequence(sequence) => sequence.handle_event(event),
         }
     }
     
@@ -187,6 +254,27 @@ impl SpinConditions {
             Self::ZoneChainContext(_, conditions) =>
                 conditions.iter().flat_map(|c| c.statuses()).collect(),
             Self::Condition(target, _) => vec![target.score >= target.target],
+            Self::Sequence(sequence) => sequence.statuses(),
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        match self {
+            Self::ChainContext(_, conditions) =>
+                conditions.iter().any(|c| c.failed()),
+            Self::ZoneChainContext(_, conditions) =>
+                conditions.iter().any(|c| c.failed()),
+            Self::Condition(_, _) => false,
+            Self::Sequence(sequence) => sequence.failed,
+        }
+    }
+
+    pub fn get_attack_table(&self) -> Option<AttackTable> {
+        match self {
+            Self::ChainContext(_, conditions)
+                | Self::ZoneChainContext(_, conditions) =>
+                conditions.iter().find_map(|c| c.get_attack_table()),
+            _ => None,
         }
     }
 }
@@ -200,6 +288,7 @@ impl SerializeUrlSafe for SpinConditions {
                 format! {"Z{}{}", handler.serialize(), conditions.serialize()},
             Self::Condition(target, scorer) => 
                 format! {"C{}{}", target.serialize(), scorer.serialize()},
+            Self::Sequence(sequence) => format! {"S{}", sequence.serialize()},
         }
     }
 
@@ -208,7 +297,8 @@ impl SerializeUrlSafe for SpinConditions {
             'H' => Self::ChainContext(ChainHandler::deserialize(input)?, Vec::deserialize(input)?),
             'Z' => Self::ZoneChainContext(ChainHandler::deserialize(input)?, Vec::deserialize(input)?),
             'C' => Self::Condition(ScoreTarget::deserialize(input)?, SpinScorer::deserialize(input)?),
-            _ => return Err(DeserializeError::new("SpinConditions type should be represented by H, Z, or C."),
+            'S' => Self::Sequence(Sequence::deserialize(input)?),
+            _ => return Err(DeserializeError::new("SpinConditions type should be represented by H, Z, C, or S."),
             )
         })
     }
@@ -232,7 +322,7 @@ pub enum SpinScorer {
     LinesCleared,
 }
 
-impl SpinScorer {
+impl Scorer<SpinEvent<'_>> for SpinScorer {
     fn score_event(&self, event: &SpinEvent) -> usize {
         match self {
             Self::LineClear{req_lines, req_piece, req_all_clear,
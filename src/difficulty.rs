@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+
+use crate::game::Board;
+use crate::puzzle::Puzzle;
+use crate::search::Move;
+use crate::solver::Solution;
+use crate::solver::SolveReport;
+use crate::solver::Solver;
+use crate::uniqueness;
+
+// Solutions looked for before assuming there are plenty
+const MAX_SOLUTIONS: usize = 10;
+
+// What makes a puzzle hard, measured by solving it
+#[derive(PartialEq, Clone)]
+pub struct Difficulty {
+    // Placements in the shortest solution
+    pub placements: usize,
+    // Average moves available at each position the solver looked at
+    pub branching_factor: f64,
+    // Distinct solutions found, up to MAX_SOLUTIONS
+    pub solutions: usize,
+    // Whether every solution found holds, spins or tucks
+    pub needs_hold: bool,
+    pub needs_spin: bool,
+    pub needs_tuck: bool,
+    // Pieces the player can see, counting current and hold
+    pub queue_length: usize,
+}
+
+impl Difficulty {
+    // None when the solver found no way to win
+    pub fn from_report(puzzle: &Puzzle, report: &SolveReport) -> Option<Self> {
+        let solutions = &report.solutions;
+        let placements = solutions.iter().map(|s| s.moves.len()).min()?;
+        let game = &puzzle.game;
+        Some(Self {
+            placements,
+            branching_factor: report.branching_factor(),
+            solutions: solutions.len(),
+            needs_hold: every_solution(solutions, |m, _| m.hold),
+            needs_spin: every_solution(solutions, |m, board|
+                m.placement.spin(board, &puzzle.spin_rules).is_some()),
+            needs_tuck: every_solution(solutions,
+                |m, board| m.placement.is_tuck(board)),
+            queue_length: game.queue.fill() + game.piece.is_some() as usize
+                + game.hold.is_some() as usize,
+        })
+    }
+
+    // Roughly how many guesses it would take to stumble on a solution, as
+    // bits, plus a bonus for each technique the puzzle forces
+    pub fn rating(&self) -> u32 {
+        let choices = self.placements as f64
+            * self.branching_factor.max(1.0).log2()
+            - (self.solutions.max(1) as f64).log2();
+        let mut rating = 10.0 * choices.max(0.0)
+            + 2.0 * self.queue_length as f64;
+        if self.needs_hold {
+            rating += 15.0;
+        }
+        if self.needs_spin {
+            rating += 25.0;
+        }
+        if self.needs_tuck {
+            rating += 20.0;
+        }
+        rating.round() as u32
+    }
+}
+
+// Whether each solution has at least one move with the given property
+fn every_solution<F>(solutions: &[Solution], f: F) -> bool
+where   F: Fn(&Move, &Board) -> bool {
+    solutions.iter().all(|s| s.moves.iter().zip(s.boards.iter())
+        .any(|(m, board)| f(m, board)))
+}
+
+pub fn estimate(puzzle: &Puzzle) -> Option<Difficulty> {
+    let mut seen = HashSet::new();
+    let report = Solver::default().distinct_solutions(puzzle, MAX_SOLUTIONS,
+        |solution| seen.insert(uniqueness::solution_key(solution)));
+    Difficulty::from_report(puzzle, &report)
+}
+
+// Stores the rating in the puzzle's metadata, clearing it if unsolved
+pub fn rate(puzzle: &mut Puzzle) -> Option<u32> {
+    puzzle.metadata.difficulty = estimate(puzzle).map(|d| d.rating());
+    puzzle.metadata.difficulty
+}
+
+// Easiest first, with unrated puzzles at the end
+pub fn sort_by_difficulty(puzzles: &mut [Puzzle]) {
+    puzzles.sort_by_key(|p| p.metadata.difficulty.unwrap_or(u32::MAX));
+}
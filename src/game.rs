@@ -12,6 +12,7 @@ pub use mino::Mino;
 
 pub mod board;
 pub use board::Board;
+pub use board::BoardMask;
 
 pub mod active_piece;
 pub use active_piece::ActivePiece;
@@ -88,6 +88,15 @@ impl ActivePiece {
 		ghost
 	}
 
+	// Paints the piece where it is, skipping cells outside the board
+	pub fn stamp(&self, board: &mut Board, mino: Option<Mino>) {
+		for pos in self.get_mino_positions() {
+			if Board::in_bounds(pos) {
+				board[pos] = mino;
+			}
+		}
+	}
+
 	pub fn place(self, board: &mut Board) {
 		for pos in self.get_ghost(board).get_mino_positions() {
 			board[pos] = Some(Mino::Piece(self.piece_type));
@@ -3,10 +3,41 @@ use crate::serialize::DeserializeError;
 use crate::serialize::SerializeUrlSafe;
 use crate::game::Mino;
 use crate::position::Position;
+use crate::position::Vector;
 
 use std::ops::IndexMut;
 use std::ops::Index;
 
+// Moves every cell by vec, dropping cells pushed out and filling with empty
+fn shift_matrix<T: Copy>(matrix: &mut [[T; 10]; 26], vec: Vector, empty: T) {
+	let old = *matrix;
+	for (y, row) in matrix.iter_mut().enumerate() {
+		for (x, cell) in row.iter_mut().enumerate() {
+			let from_x = x as i32 - vec.x;
+			let from_y = y as i32 - vec.y;
+			*cell = if (from_x as usize) < 10 && (from_y as usize) < 26 {
+				old[from_y as usize][from_x as usize]
+			} else {
+				empty
+			};
+		}
+	}
+}
+
+fn insert_matrix_row<T: Copy>(matrix: &mut [[T; 10]; 26], y: usize, row: [T; 10]) {
+	for i in (y..26-1).rev() {
+		matrix[i+1] = matrix[i];
+	}
+	matrix[y] = row;
+}
+
+fn delete_matrix_row<T: Copy>(matrix: &mut [[T; 10]; 26], y: usize, empty: T) {
+	for i in y..26-1 {
+		matrix[i] = matrix[i+1];
+	}
+	matrix[26-1] = [empty; 10];
+}
+
 #[derive(Clone, Eq, Hash, PartialEq)]
 pub struct Board {
 	pub matrix: [[Option<Mino>; 10]; 26]
@@ -57,6 +88,87 @@ impl Board {
 	}
 }
 
+impl Board {
+	pub fn mask(&self) -> BoardMask {
+		BoardMask {
+			matrix: self.matrix.map(|row| row.map(|m| m.is_some())),
+		}
+	}
+
+	pub fn height(&self) -> usize {
+		self.matrix.iter()
+			.rposition(|row| row.iter().any(|m| m.is_some()))
+			.map_or(0, |y| y + 1)
+	}
+
+	pub fn is_row_full(&self, y: usize) -> bool {
+		self.matrix[y].iter().all(|m| m.is_some())
+	}
+
+	pub fn fill_row(&mut self, y: usize, mino: Option<Mino>) {
+		self.matrix[y] = [mino; 10];
+	}
+
+	// Repaints the cells connected to pos that match its contents
+	pub fn flood_fill(&mut self, pos: Position, mino: Option<Mino>) {
+		if !Board::in_bounds(pos) {
+			return;
+		}
+		let from = self[pos];
+		if from == mino {
+			return;
+		}
+		let mut stack = vec![pos];
+		while let Some(pos) = stack.pop() {
+			if !Board::in_bounds(pos) || self[pos] != from {
+				continue;
+			}
+			self[pos] = mino;
+			for vec in [Vector::ONE_LEFT, Vector::ONE_RIGHT,
+					Vector::ONE_DOWN, Vector::ONE_UP] {
+				stack.push(pos + vec);
+			}
+		}
+	}
+
+	pub fn shift(&mut self, vec: Vector) {
+		shift_matrix(&mut self.matrix, vec, None);
+	}
+
+	// Pushes the rows from y upwards, losing the top row
+	pub fn insert_row(&mut self, y: usize, row: [Option<Mino>; 10]) {
+		insert_matrix_row(&mut self.matrix, y, row);
+	}
+
+	pub fn delete_row(&mut self, y: usize) {
+		delete_matrix_row(&mut self.matrix, y, None);
+	}
+
+	pub fn is_garbage_row(&self, y: usize) -> bool {
+		self.matrix[y].contains(&Some(Mino::Gray))
+			&& self.matrix[y].iter().all(|m| m.is_none() || *m == Some(Mino::Gray))
+	}
+
+	// Swaps two columns in every row made only of garbage
+	pub fn transpose_garbage(&mut self, from: usize, to: usize) {
+		for y in 0..26 {
+			if self.is_garbage_row(y) {
+				self.matrix[y].swap(from, to);
+			}
+		}
+	}
+
+	pub fn holes(&self) -> usize {
+		(0..10).map(|x| {
+			let height = self.matrix.iter()
+				.rposition(|row| row[x].is_some())
+				.map_or(0, |y| y + 1);
+			self.matrix[0..height].iter()
+				.filter(|row| row[x].is_none()).count()
+		}).sum()
+	}
+}
+
 impl Index<Position> for Board {
 	type Output = Option<Mino>;
 	fn index(&self, pos: Position) -> &Option<Mino> {
@@ -81,7 +193,7 @@ impl SerializeUrlSafe for Board {
 			} else if row.iter().cloned().all(|m| m == Some(Mino::Gray)) {
 				"F".to_owned()
 			} else if row.iter().cloned().all(|m| m == None || m == Some(Mino::Gray)) {
-				let mut row = row.clone();
+				let mut row = *row;
 				row.reverse();
 				format! {"G{}", row.map(|m| m == Some(Mino::Gray)).serialize()}
 			} else {
@@ -108,4 +220,82 @@ impl SerializeUrlSafe for Board {
 			matrix: matrix,
 		})
 	}
+}
+
+#[derive(Default, Clone, Eq, Hash, PartialEq)]
+pub struct BoardMask {
+	pub matrix: [[bool; 10]; 26]
+}
+
+impl BoardMask {
+	pub fn new() -> BoardMask {
+		BoardMask::default()
+	}
+
+	pub fn count(&self) -> usize {
+		self.matrix.iter()
+			.map(|row| row.iter().filter(|b| **b).count()).sum()
+	}
+
+	pub fn shift(&mut self, vec: Vector) {
+		shift_matrix(&mut self.matrix, vec, false);
+	}
+
+	pub fn insert_row(&mut self, y: usize, row: [bool; 10]) {
+		insert_matrix_row(&mut self.matrix, y, row);
+	}
+
+	pub fn delete_row(&mut self, y: usize) {
+		delete_matrix_row(&mut self.matrix, y, false);
+	}
+}
+
+impl Index<Position> for BoardMask {
+	type Output = bool;
+	fn index(&self, pos: Position) -> &bool {
+		if pos.x as usize >= 10 || pos.y as usize >= 26 {
+			return &false
+		}
+		&self.matrix[pos.y as usize][pos.x as usize]
+	}
+}
+
+impl IndexMut<Position> for BoardMask {
+	fn index_mut(&mut self, pos: Position) -> &mut bool {
+		&mut self.matrix[pos.y as usize][pos.x as usize]
+	}
+}
+
+impl SerializeUrlSafe for BoardMask {
+	fn serialize(&self) -> String {
+		self.matrix.iter().map(|row|
+			if row.iter().all(|b| !b) {
+				"E".to_owned()
+			} else if row.iter().all(|b| *b) {
+				"F".to_owned()
+			} else {
+				let mut row = *row;
+				row.reverse();
+				format! {"G{}", row.serialize()}
+			}
+		).collect()
+	}
+
+	fn deserialize(input: &mut crate::serialize::DeserializeInput) -> Result<Self, crate::serialize::DeserializeError> {
+		let mut matrix = [[false; 10]; 26];
+		for row in matrix.iter_mut() {
+			match input.next()? {
+				'E' => *row = [false; 10],
+				'F' => *row = [true; 10],
+				'G' => {
+					*row = <[bool; 10]>::deserialize(input)?;
+					row.reverse();
+				}
+				_ => return Err(DeserializeError::new("Mask row encoding types should be represented by E, F, or G.")),
+			}
+		}
+		Ok(BoardMask {
+			matrix,
+		})
+	}
 }
\ No newline at end of file
@@ -19,7 +19,7 @@ use crate::serialize::SerializeUrlSafe;
 
 use std::fmt;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
 pub enum Action {
 	MoveLeft,
 	MoveRight,
@@ -30,6 +30,43 @@ pub enum Action {
 	HoldPiece(Rotation),
 	ToggleZone,
 	Init,
+	// Asking for a hint or for the bot to play, which costs points
+	UseHint,
+}
+
+impl SerializeUrlSafe for Action {
+	fn serialize(&self) -> String {
+		match self {
+			Self::MoveLeft => "L".to_owned(),
+			Self::MoveRight => "R".to_owned(),
+			Self::MoveDown => "D".to_owned(),
+			Self::Rotate(rot) => format! {"O{}", rot.serialize()},
+			Self::SpawnPiece(irs, ihs) =>
+				format! {"S{}{}", irs.serialize(), ihs.serialize()},
+			Self::PlacePiece => "P".to_owned(),
+			Self::HoldPiece(irs) => format! {"H{}", irs.serialize()},
+			Self::ToggleZone => "Z".to_owned(),
+			Self::Init => "I".to_owned(),
+			Self::UseHint => "U".to_owned(),
+		}
+	}
+
+	fn deserialize(input: &mut serialize::DeserializeInput) -> Result<Self, serialize::DeserializeError> {
+		Ok(match input.next()? {
+			'L' => Self::MoveLeft,
+			'R' => Self::MoveRight,
+			'D' => Self::MoveDown,
+			'O' => Self::Rotate(Rotation::deserialize(input)?),
+			'S' => Self::SpawnPiece(Rotation::deserialize(input)?,
+				bool::deserialize(input)?),
+			'P' => Self::PlacePiece,
+			'H' => Self::HoldPiece(Rotation::deserialize(input)?),
+			'Z' => Self::ToggleZone,
+			'I' => Self::Init,
+			'U' => Self::UseHint,
+			_ => return Err(DeserializeError::new("Action should be represented by L, R, D, O, S, P, H, Z, I, or U.")),
+		})
+	}
 }
 
 #[derive(Hash, Eq, PartialEq, Clone)]
@@ -40,13 +77,32 @@ pub struct LineClear {
 	pub in_zone: bool,
 }
 
+impl LineClear {
+	pub fn board_after(&self) -> Board {
+		let mut board = self.board.clone();
+		self.active.clone().place(&mut board);
+		if self.in_zone {
+			board.clear_lines_zone();
+		} else {
+			board.clear_lines();
+		}
+		board
+	}
+}
+
 #[derive(Hash, Eq, PartialEq, Clone)]
 pub enum Event {
 	LineClear(LineClear),
 	ZoneClear(usize),
+	ZoneStart,
 	Spawn,
-	Move,
+	Move(Vector),
 	Rotate(usize),
+	Hold,
+	Place(usize),
+	// Every action the player sends, whether or not it does anything
+	Input(Action),
+	Hint,
 }
 
 #[derive(Clone, Eq, Hash, PartialEq)]
@@ -84,7 +140,7 @@ impl Game {
 	where	F: FnMut(&Event) {
 		if let Some(MaybeActive::Active(active)) = &mut self.piece {
 			if active.try_move(&self.board, vec) {
-				event_handler(&Event::Move);
+				event_handler(&Event::Move(vec));
 			}
 		}
 	}
@@ -123,6 +179,7 @@ impl Game {
 		}
 		if let Some(current) = self.get_current() {
 			self.has_held = true;
+			event_handler(&Event::Hold);
 			let swap = self.hold.unwrap_or_else(|| {
 				self.queue.next(info)
 			});
@@ -169,9 +226,11 @@ impl Game {
 		};
 		let board = self.board.clone();
 		let ghost = active.get_ghost(&self.board);
+		let drop = (active.pos.y - ghost.pos.y) as usize;
 		let height = ghost.get_mino_positions().iter()
 			.map(|p| p.y).min().unwrap_or(0);
 		active.place(&mut self.board);
+		event_handler(&Event::Place(drop));
 		let lines = if self.in_zone {
 			self.board.clear_lines_zone()
 		} else {
@@ -191,7 +250,9 @@ impl Game {
 	fn toggle_in_zone<F>(&mut self, event_handler: &mut F)
 	where	F: FnMut(&Event) {
 		self.in_zone = !self.in_zone;
-		if !self.in_zone {
+		if self.in_zone {
+			event_handler(&Event::ZoneStart);
+		} else {
 			let lines = self.board.clear_lines();
 			event_handler(&Event::ZoneClear(lines));
 		}
@@ -203,6 +264,10 @@ impl Game {
 		if self.over {
 			return;
 		}
+		match action {
+			Action::SpawnPiece(_, _) | Action::Init | Action::UseHint => (),
+			_ => event_handler(&Event::Input(action)),
+		}
 		match action {
 			Action::MoveLeft =>
 				self.move_piece(Vector::ONE_LEFT, event_handler),
@@ -218,6 +283,7 @@ impl Game {
 				self.hold_if_active(irs, info, event_handler),
 			Action::ToggleZone => self.toggle_in_zone(event_handler),
 			Action::Init => self.init(info),
+			Action::UseHint => event_handler(&Event::Hint),
 		}
 	}
 }
@@ -0,0 +1,67 @@
+use crate::bot::Bot;
+use crate::game::ActivePiece;
+use crate::puzzle::Puzzle;
+use crate::search;
+use crate::search::Move;
+use crate::solver::Solver;
+
+#[derive(Hash, Eq, PartialEq, Copy, Clone)]
+pub enum HintSource {
+    // Written into the puzzle's metadata by its author
+    Authored,
+    Solver,
+    // No win was found, so this is just a placement the bot likes
+    Bot,
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct Hint {
+    pub source: HintSource,
+    // Whether the piece comes from hold or the queue instead of being current
+    pub hold: bool,
+    pub piece: ActivePiece,
+}
+
+impl Hint {
+    fn from_move(source: HintSource, m: Move) -> Self {
+        Self {
+            source,
+            hold: m.hold,
+            piece: m.placement.piece,
+        }
+    }
+}
+
+// The authored hint for this piece, if it can still be reached
+fn authored(puzzle: &Puzzle, placed: usize) -> Option<Move> {
+    let hint = puzzle.metadata.hints.get(placed)?;
+    let mut cells = hint.get_mino_positions();
+    cells.sort_by_key(|p| (p.y, p.x));
+    search::moves(&puzzle.game, true).into_iter().find(|m|
+        m.placement.piece.piece_type == hint.piece_type
+        && m.placement.cells() == cells)
+}
+
+// The next placement toward the win conditions. Authored hints come first,
+// then the solver, then the bot when the solver can't find a win.
+// Placed is how many pieces have been placed since the puzzle started.
+pub fn find_hint(puzzle: &Puzzle, placed: usize) -> Option<Hint> {
+    if puzzle.over {
+        return None;
+    }
+    if let Some(m) = authored(puzzle, placed) {
+        return Some(Hint::from_move(HintSource::Authored, m));
+    }
+    if !puzzle.won && !puzzle.win_conditions.conditions.is_empty() {
+        let first = Solver::default().solve(puzzle)
+            .and_then(|solution| solution.into_iter().next());
+        if let Some(m) = first {
+            return Some(Hint::from_move(HintSource::Solver, m));
+        }
+    }
+    Bot::default().find_move(puzzle).map(|m| Hint {
+        source: HintSource::Bot,
+        hold: m.hold,
+        piece: m.placement.piece,
+    })
+}
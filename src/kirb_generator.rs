@@ -5,10 +5,13 @@ use rand::Rng;
 use rand::rngs::ThreadRng;
 use rand::seq::SliceRandom;
 
+use crate::board_analysis::BoardAnalysis;
+use crate::difficulty;
 use crate::condition::event::Conditions;
 use crate::condition::event::EventConditions;
 use crate::condition::event::ReqOrMin;
 use crate::condition::event::ScoreTarget;
+use crate::condition::spin::SpinRules;
 use crate::game::ActivePiece;
 use crate::game::BagRandomizer;
 use crate::game::Board;
@@ -17,11 +20,14 @@ use crate::game::MaybeActive;
 use crate::game::Mino;
 use crate::game::PieceType;
 use crate::game::Queue;
+use crate::metadata::Metadata;
 use crate::position::Position;
 use crate::position::Rotation;
 use crate::position::Vector;
 use crate::puzzle::Puzzle;
 use crate::replay::Info;
+use crate::scoring::Scoring;
+use crate::transform::Mirror;
 
 
 fn generate_ending_board(rng: &mut ThreadRng) -> Board {
@@ -64,7 +70,7 @@ fn generate_ending_board(rng: &mut ThreadRng) -> Board {
     board
 }
 
-fn try_remove(board: &Board, placement: &ActivePiece) -> Option<Board> {
+pub(crate) fn try_remove(board: &Board, placement: &ActivePiece) -> Option<Board> {
     let mut board = board.clone();
     let mut lockout = true;
     for mino in placement.get_mino_positions() {
@@ -84,7 +90,7 @@ fn try_remove(board: &Board, placement: &ActivePiece) -> Option<Board> {
     Some(board)    
 }
 
-fn can_place(board: &Board, placement: ActivePiece, irs: Rotation) -> bool {
+pub(crate) fn can_place(board: &Board, placement: ActivePiece, irs: Rotation) -> bool {
     let mut queue = VecDeque::new();
     let mut reachable = HashSet::new();
     if let Some(active) = ActivePiece::spawn(board, placement.piece_type, irs) {
@@ -117,7 +123,7 @@ fn can_place(board: &Board, placement: ActivePiece, irs: Rotation) -> bool {
     reachable.contains(&placement)
 }
 
-fn can_spawn(board: &Board, piece: PieceType, irs: Rotation) -> bool {
+pub(crate) fn can_spawn(board: &Board, piece: PieceType, irs: Rotation) -> bool {
     ActivePiece::spawn(board, piece, irs).is_some()
 }
 
@@ -200,7 +206,7 @@ fn remove_piece_skim(rng: &mut ThreadRng, board: Board, piece_type: PieceType,
     None
 }
 
-fn generate_order(rng: &mut ThreadRng, queue: &[PieceType])
+pub(crate) fn generate_order(rng: &mut ThreadRng, queue: &[PieceType])
         -> Vec<(PieceType, PieceType, bool)> {
     let mut order = Vec::new();
 
@@ -238,22 +244,8 @@ fn try_generate(rng: &mut ThreadRng, queue: &[PieceType]) -> Option<Board> {
 }
 
 fn rate_board(board: &Board) -> usize {
-    let mut score = 0;
-    for y in 0..26 {
-        for x in 0..10 {
-            let pos = Position::new(x, y);
-            for dir in [Vector::ONE_LEFT, Vector::ONE_RIGHT,
-                    Vector::ONE_UP, Vector::ONE_DOWN] {
-                if board[pos].is_some() && board[pos + dir].is_none() {
-                    score += 2;
-                }
-            }
-            if board[pos].is_some() && board[pos + Vector::ONE_DOWN].is_none() {
-                score += 100;
-            }
-        }
-    }
-    score
+    let analysis = BoardAnalysis::new(board);
+    2 * analysis.exposed_edges + 100 * analysis.overhangs
 }
 
 fn default_board() -> Board {
@@ -300,7 +292,8 @@ pub fn generate(difficulty: u32) -> Puzzle {
         queue: Queue {
             length: 4,
             pieces: queue[2..].iter().cloned().collect(),
-            rando: BagRandomizer::new(),
+            // Carry on from the bag the queue was drawn from
+            rando,
         },
         hold: queue.get(0).cloned(),
         has_held: false,
@@ -308,6 +301,8 @@ pub fn generate(difficulty: u32) -> Puzzle {
         in_zone: true,
         over: false,
     };
+    // The boards lean one way, so flip half of them
+    let game = if rng.gen_bool(0.5) { game.mirror() } else { game };
     Puzzle {
         game,
         win_conditions: Conditions {
@@ -332,5 +327,41 @@ pub fn generate(difficulty: u32) -> Puzzle {
         },
         won: false,
         over: false,
+        scoring: Scoring::default(),
+        spin_rules: SpinRules::default(),
+        metadata: Metadata::default(),
+    }
+}
+
+// Estimated ratings climb by about this much per step of raw difficulty
+const RATING_PER_DIFFICULTY: u32 = 30;
+const BASE_RATING: u32 = 180;
+const RATED_ATTEMPTS: usize = 5;
+
+// Generates puzzles until one's estimated rating lands near the target,
+// stepping the raw difficulty toward it and keeping the closest. Puzzles
+// that can't be solved from the pieces shown are skipped.
+pub fn generate_rated(rating: u32) -> Puzzle {
+    let mut raw = rating.saturating_sub(BASE_RATING) / RATING_PER_DIFFICULTY;
+    let mut best: Option<(u32, Puzzle)> = None;
+    for _ in 0..RATED_ATTEMPTS {
+        let mut puzzle = generate(raw);
+        let estimate = match difficulty::rate(&mut puzzle) {
+            Some(estimate) => estimate,
+            None => continue,
+        };
+        let distance = estimate.abs_diff(rating);
+        if best.as_ref().is_none_or(|(d, _)| distance < *d) {
+            best = Some((distance, puzzle));
+        }
+        if distance <= RATING_PER_DIFFICULTY / 2 {
+            break;
+        }
+        if estimate < rating {
+            raw += 1;
+        } else {
+            raw = raw.saturating_sub(1);
+        }
     }
+    best.map_or_else(|| generate(raw), |(_, puzzle)| puzzle)
 }
\ No newline at end of file
@@ -6,11 +6,26 @@ pub mod position;
 pub mod game;
 
 pub mod replay;
+pub mod verify;
 
 pub mod serialize;
 
+pub mod scoring;
+pub mod stats;
+
 pub mod puzzle;
+pub mod validation;
+pub mod metadata;
+pub mod transform;
+pub mod board_analysis;
+pub mod search;
+pub mod bot;
+pub mod solver;
+pub mod hint;
+pub mod difficulty;
+pub mod uniqueness;
 pub mod condition;
 
 pub mod kirb_generator;
-pub mod tsd_generator;
\ No newline at end of file
+pub mod tsd_generator;
+pub mod pc_generator;
\ No newline at end of file
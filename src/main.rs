@@ -1,4 +1,72 @@
+use std::env;
+use std::str::FromStr;
+
+use ztrix::bot::Bot;
+use ztrix::bot::Weights;
+use ztrix::difficulty;
+use ztrix::puzzle::Puzzle;
+use ztrix::replay::Replay;
+
+// Milliseconds between the bot's pieces in the recorded replay
+const SELF_PLAY_DELAY: usize = 250;
+
+fn self_play(pieces: usize, weights: Weights) {
+    let bot = Bot::new(weights);
+    let mut replay = Replay::new(Puzzle::default(), &mut |_| ());
+    for piece in 0..pieces {
+        replay.set_time((piece + 1) * SELF_PLAY_DELAY);
+        if !bot.play(&mut replay, &mut |_| ()) {
+            break;
+        }
+    }
+    println!("https://ztrix-game.web.app/replay/{}", replay.get_log());
+    println!("{}", replay.get_stats().to_json(replay.get_duration()));
+}
+
+// Generates puzzles rated around the target, printed easiest first
+fn pack(count: usize, rating: u32) {
+    let mut puzzles: Vec<Puzzle> = (0..count)
+        .map(|_| Puzzle::generate_rated_kirb_puzzle(rating))
+        .collect();
+    difficulty::sort_by_difficulty(&mut puzzles);
+    for puzzle in puzzles {
+        let rating = puzzle.metadata.difficulty
+            .map_or("unrated".to_owned(), |r| r.to_string());
+        println!("{}\thttps://ztrix-game.web.app/puzzle/{}", rating, puzzle);
+    }
+}
+
 fn main() {
-    let puzzle = ztrix::tsd_generator::generate();
-    println!("{}", puzzle);
-}
\ No newline at end of file
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(|a| a.as_str()) {
+        // selfplay [pieces] [survival | t_spin | serialized weights]
+        Some("selfplay") => {
+            let pieces = args.get(2)
+                .and_then(|p| p.parse().ok()).unwrap_or(100);
+            let weights = match args.get(3).map(|w| w.as_str()) {
+                None | Some("survival") => Weights::survival(),
+                Some("t_spin") => Weights::t_spin(),
+                Some(weights) => match Weights::from_str(weights) {
+                    Ok(weights) => weights,
+                    Err(_) => {
+                        eprintln!("Weights should be survival, t_spin, or serialized weights.");
+                        return;
+                    }
+                },
+            };
+            self_play(pieces, weights);
+        }
+        // pack [count] [rating]
+        Some("pack") => {
+            let count = args.get(2)
+                .and_then(|c| c.parse().ok()).unwrap_or(10);
+            let rating = args.get(3)
+                .and_then(|r| r.parse().ok()).unwrap_or(200);
+            pack(count, rating);
+        }
+        _ => {
+            let puzzle = ztrix::tsd_generator::generate();
+            println!("{}", puzzle);
+        }
+    }
+}
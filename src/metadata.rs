@@ -0,0 +1,41 @@
+use crate::game::ActivePiece;
+use crate::serialize::DeserializeError;
+use crate::serialize::SerializeUrlSafe;
+
+// Information about a puzzle that doesn't change how it plays
+#[derive(Hash, Eq, PartialEq, Clone, Default)]
+pub struct Metadata {
+    // The intended placement for each piece, in the order they're placed
+    pub hints: Vec<ActivePiece>,
+    // The estimated difficulty rating, higher is harder
+    pub difficulty: Option<u32>,
+}
+
+// Each field is written with a tag and only when it's set, so fields can be
+// added without breaking older links
+impl SerializeUrlSafe for Metadata {
+    fn serialize(&self) -> String {
+        let mut string = String::new();
+        if !self.hints.is_empty() {
+            string.push('H');
+            string.push_str(&self.hints.serialize());
+        }
+        if let Some(difficulty) = self.difficulty {
+            string.push('D');
+            string.push_str(&difficulty.serialize());
+        }
+        string + "."
+    }
+
+    fn deserialize(input: &mut crate::serialize::DeserializeInput) -> Result<Self, crate::serialize::DeserializeError> {
+        let mut metadata = Self::default();
+        while !input.next_if('.')? {
+            match input.next()? {
+                'H' => metadata.hints = Vec::deserialize(input)?,
+                'D' => metadata.difficulty = Some(u32::deserialize(input)?),
+                _ => return Err(DeserializeError::new("Metadata fields should be tagged with H or D.")),
+            }
+        }
+        Ok(metadata)
+    }
+}
@@ -0,0 +1,412 @@
+use rand::rngs::ThreadRng;
+use rand::seq::SliceRandom;
+
+use crate::condition::all_clear::AllClearType;
+use crate::condition::event::Conditions;
+use crate::condition::event::EventConditions;
+use crate::condition::event::EventScorer;
+use crate::condition::event::ReqOrMin;
+use crate::condition::event::ScoreTarget;
+use crate::condition::spin::SpinRules;
+use crate::game::ActivePiece;
+use crate::game::BagRandomizer;
+use crate::game::Board;
+use crate::game::Game;
+use crate::game::MaybeActive;
+use crate::game::Mino;
+use crate::game::PieceType;
+use crate::game::Queue;
+use crate::kirb_generator;
+use crate::metadata::Metadata;
+use crate::position::Position;
+use crate::position::Rotation;
+use crate::puzzle::Puzzle;
+use crate::replay::Info;
+use crate::search::Placement;
+use crate::scoring::Scoring;
+
+pub const MIN_HEIGHT: usize = 2;
+pub const MAX_HEIGHT: usize = 6;
+
+const ATTEMPTS: usize = 10;
+// Boards visited by one attempt at building a puzzle backward
+const MAX_NODES: usize = 300;
+// Solutions built for each queue to pick the difficulty from
+const CANDIDATES: usize = 3;
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct PcSettings {
+    // Lines cleared by the perfect clear
+    pub height: usize,
+    // Pieces to place. Any cells they don't fill start out as garbage.
+    pub pieces: usize,
+    // Whether hold can be used. If so, the intended solution swaps at random.
+    pub hold: bool,
+    // Tucks, hold swaps and early line clears to aim for in the solution.
+    // Most queues land between one a line and one a piece.
+    pub difficulty: u32,
+}
+
+impl PcSettings {
+    // The most pieces a perfect clear of this height can take
+    pub fn max_pieces(height: usize) -> usize {
+        height * 10 / 4
+    }
+
+    // Two pieces a line, leaving a little garbage to build around, at the
+    // easy end of difficulty. Random queues often can't clear a full board,
+    // so those are slow to find.
+    pub fn new(height: usize) -> Self {
+        let height = height.clamp(MIN_HEIGHT, MAX_HEIGHT);
+        Self {
+            height,
+            pieces: 2 * height,
+            hold: true,
+            difficulty: height as u32,
+        }
+    }
+}
+
+impl Default for PcSettings {
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+// Puts full rows back where a line clear took them out. The rows are
+// counted on the board as it was before the clear, and must be sorted.
+fn unclear_rows(board: &Board, rows: &[usize]) -> Board {
+    let mut unclear = Board::new();
+    let mut old_rows = board.matrix.iter();
+    for (y, row) in unclear.matrix.iter_mut().enumerate() {
+        *row = if rows.contains(&y) {
+            [Some(Mino::Gray); 10]
+        } else {
+            old_rows.next().cloned().unwrap_or([None; 10])
+        };
+    }
+    unclear
+}
+
+// Every set of rows one piece could clear out of the bottom `total`. A
+// piece is at most four tall, so they have to sit within four rows.
+fn row_choices(total: usize, lines: usize) -> Vec<Vec<usize>> {
+    if lines == 0 {
+        return vec![Vec::new()];
+    }
+    let mut choices = Vec::new();
+    for bottom in 0..total {
+        let above = (bottom + 1..(bottom + 4).min(total)).collect::<Vec<usize>>();
+        for mask in 0..1usize << above.len() {
+            if mask.count_ones() as usize != lines - 1 {
+                continue;
+            }
+            let mut rows = vec![bottom];
+            rows.extend(above.iter().enumerate()
+                .filter(|(i, _)| mask & 1 << i != 0)
+                .map(|(_, row)| *row));
+            choices.push(rows);
+        }
+    }
+    choices
+}
+
+// The pieces that could come off the board while touching every one of the
+// cleared rows, so putting them back clears exactly those
+fn removals(board: &Board, cleared: &[usize], piece_type: PieceType,
+        held: PieceType, req_no_irs: bool) -> Vec<(Board, ActivePiece)> {
+    let mut options = Vec::new();
+    for r in 0..4 {
+        let rot = Rotation::from_num_cw(r);
+        for y in 0..(MAX_HEIGHT + 2) as i32 {
+            for x in 0..10 {
+                let pos = Position::new(x, y);
+                let placement = ActivePiece {piece_type, pos, rot};
+                let minos = placement.get_mino_positions();
+                if !cleared.iter().all(|row|
+                        minos.iter().any(|mino| mino.y == *row as i32)) {
+                    continue;
+                }
+                let board = match kirb_generator::try_remove(board, &placement) {
+                    Some(board) => board,
+                    None => continue,
+                };
+                let irs_list = [Rotation::Zero, Rotation::Anticlockwise, Rotation::Clockwise];
+                let mut iter = irs_list.into_iter().map(|irs| {
+                    kirb_generator::can_spawn(&board, held, irs)
+                            && placement == placement.get_ghost(&board)
+                            && kirb_generator::can_place(&board, placement.clone(), irs)
+                });
+                let is_valid = if req_no_irs {
+                    iter.all(|b| b)
+                } else {
+                    iter.any(|b| b)
+                };
+                if is_valid {
+                    options.push((board, placement));
+                }
+            }
+        }
+    }
+    options
+}
+
+// Cells past a multiple of four in each connected group. Groups made of
+// whole pieces have none, so these are cells that need garbage or lines
+// put back to come off.
+fn leftover_cells(board: &Board) -> usize {
+    let mut seen = [[false; 10]; 26];
+    let mut leftover = 0;
+    for start_y in 0..26 {
+        for start_x in 0..10 {
+            if seen[start_y][start_x] || board.matrix[start_y][start_x].is_none() {
+                continue;
+            }
+            seen[start_y][start_x] = true;
+            let mut stack = vec![(start_x, start_y)];
+            let mut size = 0;
+            while let Some((x, y)) = stack.pop() {
+                size += 1;
+                let neighbours = [(x.wrapping_sub(1), y), (x + 1, y),
+                    (x, y.wrapping_sub(1)), (x, y + 1)];
+                for (x, y) in neighbours {
+                    if x < 10 && y < 26 && !seen[y][x]
+                            && board.matrix[y][x].is_some() {
+                        seen[y][x] = true;
+                        stack.push((x, y));
+                    }
+                }
+            }
+            leftover += size % 4;
+        }
+    }
+    leftover
+}
+
+// Whether the cells could still be split into `pieces` pieces plus garbage
+fn can_split(board: &Board, pieces: usize) -> bool {
+    let cells = board.matrix.iter().flatten().filter(|c| c.is_some()).count();
+    cells.checked_sub(4 * pieces)
+        .is_some_and(|garbage| leftover_cells(board) <= garbage)
+}
+
+// Takes the last piece in the order off the board, putting back the lines it
+// cleared, and carries on with the rest. Backtracks out of boards the earlier
+// pieces can't be taken off of, giving up after MAX_NODES boards. Each
+// placement is kept with whether it was a tuck and whether it cleared lines
+// before the perfect clear, as those make the solution harder to see.
+fn build_back(rng: &mut ThreadRng, height: usize,
+        order: &[(PieceType, PieceType, bool)], board: Board, lines: usize,
+        placements: &mut Vec<(ActivePiece, u32)>, nodes: &mut usize)
+        -> Option<Board> {
+    let ((piece, held, req_no_irs), rest) = match order.split_last() {
+        Some(split) => split,
+        None => return Some(board),
+    };
+    if *nodes >= MAX_NODES {
+        return None;
+    }
+    *nodes += 1;
+
+    // The last piece makes the perfect clear, and the lines left over have
+    // to fit in the pieces before this one
+    let left = height - lines;
+    let min = left.saturating_sub(4 * rest.len())
+        .max(if placements.is_empty() { 1 } else { 0 });
+    let mut options = Vec::new();
+    for cleared in min..=left.min(4) {
+        for rows in row_choices(lines + cleared, cleared) {
+            options.extend(removals(&unclear_rows(&board, &rows), &rows,
+                    *piece, *held, *req_no_irs).into_iter()
+                .map(|(board, placement)| (board, placement, cleared)));
+        }
+    }
+    // Once every line is back the board only loses cells, so check it can
+    // still be taken apart
+    options.retain(|(board, _, cleared)|
+        lines + cleared < height || can_split(board, rest.len()));
+    options.shuffle(rng);
+
+    let last = placements.is_empty();
+    for (board, placement, cleared) in options {
+        let tuck = Placement {
+            piece: placement.clone(),
+            kick: None,
+            actions: Vec::new(),
+        }.is_tuck(&board);
+        let early_clear = cleared > 0 && !last;
+        placements.push((placement, u32::from(tuck) + u32::from(early_clear)));
+        let start = build_back(rng, height, rest, board, lines + cleared,
+            placements, nodes);
+        if start.is_some() {
+            return start;
+        }
+        placements.pop();
+    }
+    None
+}
+
+// Pieces placed from hold instead of being the current piece. Each
+// placement uses up the next piece in the queue, placed or swapped into hold.
+fn hold_swaps(queue: &[PieceType], placements: &[ActivePiece]) -> u32 {
+    queue.iter().skip(1).zip(placements.iter())
+        .filter(|(current, placement)| placement.piece_type != **current)
+        .count() as u32
+}
+
+// Works backward from the empty board left by the perfect clear. Returns
+// the starting board, the placements that solve it and how hard they are,
+// counting tucks, hold swaps and line clears before the perfect clear.
+fn try_generate(rng: &mut ThreadRng, settings: &PcSettings,
+        queue: &[PieceType]) -> Option<(Board, Vec<ActivePiece>, u32)> {
+    let order = if settings.hold {
+        kirb_generator::generate_order(rng, queue)
+    } else {
+        queue.iter().enumerate()
+            .map(|(i, piece)| (*piece, *piece, i == 0))
+            .collect()
+    };
+
+    let mut placements = Vec::new();
+    let board = build_back(rng, settings.height, &order, Board::new(), 0,
+        &mut placements, &mut 0)?;
+    placements.reverse();
+    let (placements, effort): (Vec<ActivePiece>, Vec<u32>) =
+        placements.into_iter().unzip();
+    let swaps = if settings.hold { hold_swaps(queue, &placements) } else { 0 };
+
+    Some((board, placements, effort.iter().sum::<u32>() + swaps))
+}
+
+fn build_puzzle(settings: &PcSettings, queue: &[PieceType],
+        rando: BagRandomizer, board: Board, hints: Vec<ActivePiece>) -> Puzzle {
+    // With hold, the first piece starts in hold like kirb puzzles
+    let (hold, queue) = match settings.hold {
+        true => (queue.first().cloned(), queue.get(1..).unwrap_or(&[])),
+        false => (None, queue),
+    };
+    let game = Game {
+        piece: queue.first().map(|p| MaybeActive::Inactive(*p)),
+        queue: Queue {
+            length: 0,
+            pieces: queue.iter().skip(1).cloned().collect(),
+            // Carry on from the bag the queue was drawn from
+            rando,
+        },
+        hold,
+        has_held: false,
+        board,
+        in_zone: false,
+        over: false,
+    };
+
+    let perfect_clear = EventConditions::Condition(
+        ScoreTarget {score: 0, target: 1},
+        EventScorer::LineClear {
+            req_lines: ReqOrMin::Min(1),
+            req_piece: None,
+            req_all_clear: AllClearType::ALL_CLEAR,
+            negate: false,
+        },
+    );
+    // Running out of pieces only ends the puzzle if the last one didn't
+    // win it
+    let mut end_conditions = vec![
+        EventConditions::All(vec![
+            EventConditions::Condition(
+                ScoreTarget {score: 0, target: settings.pieces},
+                EventScorer::PiecesPlaced,
+            ),
+            EventConditions::Not(Box::new(perfect_clear.clone())),
+        ]),
+    ];
+    if !settings.hold {
+        end_conditions.push(EventConditions::Condition(
+            ScoreTarget {score: 0, target: 1},
+            EventScorer::HoldsUsed,
+        ));
+    }
+
+    Puzzle {
+        game,
+        win_conditions: Conditions {
+            conditions: vec![perfect_clear],
+        },
+        end_conditions: Conditions {
+            conditions: end_conditions,
+        },
+        won: false,
+        over: false,
+        scoring: Scoring::default(),
+        spin_rules: SpinRules::default(),
+        metadata: Metadata {
+            hints,
+            ..Metadata::default()
+        },
+    }
+}
+
+// A perfect clear puzzle built from a random queue, or None if none of the
+// queues tried could be built. Once a queue builds, a few more solutions
+// for it are tried and the one nearest the difficulty kept.
+fn try_generate_puzzle(rng: &mut ThreadRng, settings: &PcSettings)
+        -> Option<Puzzle> {
+    let extra = if settings.hold { 1 } else { 0 };
+    for _ in 0..ATTEMPTS {
+        let mut rando = BagRandomizer::new();
+        let mut info = Info::new();
+        let queue = (0..settings.pieces + extra)
+            .map(|_| rando.next(&mut info))
+            .collect::<Vec<PieceType>>();
+        let first = match try_generate(rng, settings, &queue) {
+            Some(first) => first,
+            None => continue,
+        };
+        let (board, hints, _) = (1..CANDIDATES)
+            .filter_map(|_| try_generate(rng, settings, &queue))
+            .chain(std::iter::once(first))
+            .min_by_key(|(_, _, effort)| effort.abs_diff(settings.difficulty))
+            .expect("Always at least the first candidate");
+        return Some(build_puzzle(settings, &queue, rando, board, hints));
+    }
+    None
+}
+
+// Generates a perfect clear puzzle. The height is kept to 2-6 lines and the
+// pieces to what fits in it. If no puzzle can be built with that many
+// pieces, one fewer is tried, leaving more garbage.
+pub fn generate(settings: &PcSettings) -> Puzzle {
+    let height = settings.height.clamp(MIN_HEIGHT, MAX_HEIGHT);
+    let mut settings = PcSettings {
+        height,
+        pieces: settings.pieces.clamp(1, PcSettings::max_pieces(height)),
+        ..settings.clone()
+    };
+    let mut rng = rand::thread_rng();
+    loop {
+        if let Some(puzzle) = try_generate_puzzle(&mut rng, &settings) {
+            return puzzle;
+        }
+        if settings.pieces <= 1 {
+            return Puzzle::default();
+        }
+        settings.pieces -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::Solver;
+    use crate::verify;
+
+    #[test]
+    fn generated_puzzle_solves_and_verifies() {
+        let puzzle = generate(&PcSettings::new(MIN_HEIGHT));
+        let solution = Solver::default().solutions(&puzzle, 1)
+            .solutions.into_iter().next()
+            .expect("the solver should find the generated solution");
+        let log = solution.replay(&puzzle).get_log().to_string();
+        assert!(verify::verify(&puzzle, &log).is_ok());
+    }
+}
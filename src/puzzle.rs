@@ -2,15 +2,28 @@
 use std::fmt;
 use std::str::FromStr;
 
+use crate::condition::attack::AttackTable;
 use crate::condition::event::Conditions;
+use crate::condition::spin::SpinRules;
+use crate::difficulty;
+use crate::difficulty::Difficulty;
 use crate::game::Action;
 use crate::game::Game;
+use crate::game::Mino;
 use crate::game::game::Event;
 use crate::kirb_generator;
+use crate::metadata::Metadata;
+use crate::pc_generator;
+use crate::pc_generator::PcSettings;
 use crate::tsd_generator;
 use crate::replay::Info;
+use crate::scoring::Scoring;
+use crate::position::Vector;
+use crate::transform;
 use crate::serialize::DeserializeError;
 use crate::serialize::SerializeUrlSafe;
+use crate::validation;
+use crate::validation::Issue;
 
 #[derive(Hash, Eq, PartialEq, Clone)]
 pub struct Puzzle {
@@ -19,6 +32,9 @@ pub struct Puzzle {
     pub end_conditions: Conditions,
     pub won: bool,
     pub over: bool,
+    pub scoring: Scoring,
+    pub spin_rules: SpinRules,
+    pub metadata: Metadata,
 }
 
 impl Puzzle {
@@ -29,6 +45,9 @@ impl Puzzle {
             end_conditions: Conditions{ conditions: Vec::new() },
             won: false,
             over: false,
+            scoring: Scoring::default(),
+            spin_rules: SpinRules::default(),
+            metadata: Metadata::default(),
         }
     }
 
@@ -39,14 +58,16 @@ impl Puzzle {
         }
         self.game.update(action, info, &mut |event| {
             event_handler(event);
-            self.win_conditions.handle_event(event);
-            self.end_conditions.handle_event(event);
+            self.scoring.handle_event(event, &self.spin_rules);
+            self.win_conditions.handle_event(event, &self.spin_rules);
+            self.end_conditions.handle_event(event, &self.spin_rules);
         });
-        if self.win_conditions.conditions.len() > 0 &&
+        if !self.win_conditions.conditions.is_empty() &&
             self.win_conditions.statuses().iter().all(|s| *s) {
             self.won = true;
         }
-        if self.game.over || self.end_conditions.statuses().iter().any(|s| *s) {
+        if self.game.over || self.end_conditions.statuses().iter().any(|s| *s)
+            || self.win_conditions.failed() {
             self.over = true;
         }
     }
@@ -55,13 +76,51 @@ impl Puzzle {
 		&self.game
 	}
 
+    // The damage model the win conditions use, TEC's when they count none
+    pub fn attack_table(&self) -> AttackTable {
+        self.win_conditions.get_attack_table()
+            .unwrap_or_else(|| AttackTable::tec(true))
+    }
+
+    pub fn validate(&self) -> Vec<Issue> {
+        validation::validate(self)
+    }
+
+    pub fn shift(&mut self, vec: Vector) -> bool {
+        transform::shift(self, vec)
+    }
+
+    pub fn insert_row(&mut self, y: usize, row: [Option<Mino>; 10]) {
+        transform::insert_row(self, y, row)
+    }
+
+    pub fn delete_row(&mut self, y: usize) {
+        transform::delete_row(self, y)
+    }
+
+    pub fn transpose_garbage(&mut self, from: usize, to: usize) {
+        transform::transpose_garbage(self, from, to)
+    }
+
+    pub fn estimate_difficulty(&self) -> Option<Difficulty> {
+        difficulty::estimate(self)
+    }
+
     pub fn generate_kirb_puzzle(difficulty: u32) -> Self {
         kirb_generator::generate(difficulty)
     }
+
+    pub fn generate_rated_kirb_puzzle(rating: u32) -> Self {
+        kirb_generator::generate_rated(rating)
+    }
     
     pub fn generate_tsd_puzzle() -> Self {
         tsd_generator::generate()
     }
+
+    pub fn generate_pc_puzzle(settings: &PcSettings) -> Self {
+        pc_generator::generate(settings)
+    }
 }
 
 impl Default for Puzzle {
@@ -72,23 +131,46 @@ impl Default for Puzzle {
 
 impl SerializeUrlSafe for Puzzle {
     fn serialize(&self) -> String {
-        format! {"{}{}{}{}{}",
+        let string = format! {"{}{}{}{}{}{}{}",
             self.game.serialize(),
             self.win_conditions.serialize(),
             self.end_conditions.serialize(),
             self.won.serialize(),
             self.over.serialize(),
+            self.spin_rules.serialize(),
+            self.metadata.serialize(),
+        };
+        // A puzzle that hasn't been played yet keeps the same link as before
+        // scores were saved
+        if self.scoring == Scoring::default() {
+            string
+        } else {
+            string + &self.scoring.serialize()
         }
     }
 
     fn deserialize(input: &mut crate::serialize::DeserializeInput) -> Result<Self, crate::serialize::DeserializeError> {
-        Ok(Self {
+        let mut puzzle = Self {
             game: Game::deserialize(input)?,
             win_conditions: Conditions::deserialize(input)?,
             end_conditions: Conditions::deserialize(input)?,
             won: bool::deserialize(input)?,
             over: bool::deserialize(input)?,
-        })
+            scoring: Scoring::default(),
+            // Links from before spin rules were added end here
+            spin_rules: match input.peek() {
+                Ok(_) => SpinRules::deserialize(input)?,
+                Err(_) => SpinRules::default(),
+            },
+            metadata: match input.peek() {
+                Ok(_) => Metadata::deserialize(input)?,
+                Err(_) => Metadata::default(),
+            },
+        };
+        if input.peek().is_ok() {
+            puzzle.scoring = Scoring::deserialize(input)?;
+        }
+        Ok(puzzle)
     }
 }
 
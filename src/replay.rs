@@ -1,19 +1,28 @@
 use crate::game::game::Event;
 use crate::puzzle::Puzzle;
+use crate::serialize::DeserializeError;
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 use rand::RngCore;
 
 use crate::game::Action;
 
+use crate::game::BagRandomizer;
 use crate::game::Game;
+use crate::game::PieceType;
+use crate::serialize::SerializeUrlSafe;
+use crate::stats::Stats;
 
 use rand::prelude::*;
+use rand::rngs::SmallRng;
+use enumset::EnumSet;
 
 #[derive(Clone)]
 pub struct Info {
 	index: usize,
 	info: Vec<u32>,
-	generator: ThreadRng,
+	generator: SmallRng,
 }
 
 impl Info {
@@ -21,10 +30,24 @@ impl Info {
 		Info {
 			index: 0,
 			info: Vec::new(),
-			generator: rand::thread_rng(),
+			generator: SmallRng::from_entropy(),
 		}
 	}
 
+	// Draws the same values every time, so searches past the known queue
+	// give the same answer on every run
+	pub fn seeded(seed: u64) -> Info {
+		Info {
+			index: 0,
+			info: Vec::new(),
+			generator: SmallRng::seed_from_u64(seed),
+		}
+	}
+
+	pub fn get_index(&self) -> usize {
+		self.index
+	}
+
 	pub fn next_u32(&mut self) -> u32 {
 		if self.info.len() == self.index {
 			self.info.push(self.generator.next_u32());
@@ -34,39 +57,161 @@ impl Info {
 		info
 	}
 
+	pub fn from_values(info: Vec<u32>) -> Info {
+		Info {
+			index: 0,
+			info,
+			generator: SmallRng::from_entropy(),
+		}
+	}
+
+	fn choice_at<'a, T>(&self, index: usize, options: &'a [T]) -> &'a T {
+		&options[(self.info[index] as usize) % options.len()]
+	}
+
 	pub fn choice<'a, T>(&mut self, options: &'a Vec<T>) -> &'a T {
 		let choice = (self.next_u32() as usize) % options.len();
 		&options[choice]
 	}
 }
 
+// Times are in milliseconds since recording started
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct TimedAction {
+	pub time: usize,
+	pub action: Action,
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct TimedLog {
+	pub puzzle: Puzzle,
+	pub info: Vec<u32>,
+	pub actions: Vec<TimedAction>,
+}
+
+impl SerializeUrlSafe for TimedLog {
+	fn serialize(&self) -> String {
+		// Store the gaps between actions to keep times short
+		let mut prev = 0;
+		let actions: String = self.actions.iter().map(|a| {
+			let delta = a.time.saturating_sub(prev);
+			prev = a.time;
+			format! {"{}{}", a.action.serialize(), delta.serialize()}
+		}).collect();
+		format! {"{}{}.{}",
+			self.info.serialize(),
+			actions,
+			self.puzzle.serialize(),
+		}
+	}
+
+	fn deserialize(input: &mut crate::serialize::DeserializeInput) -> Result<Self, crate::serialize::DeserializeError> {
+		let info = Vec::deserialize(input)?;
+		let mut actions = Vec::new();
+		let mut time = 0;
+		while !input.next_if('.')? {
+			let action = Action::deserialize(input)?;
+			time += usize::deserialize(input)?;
+			actions.push(TimedAction { time, action });
+		}
+		Ok(Self {
+			puzzle: Puzzle::deserialize(input)?,
+			info,
+			actions,
+		})
+	}
+}
+
+impl fmt::Display for TimedLog {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.serialize())
+	}
+}
+
+impl FromStr for TimedLog {
+	type Err = DeserializeError;
+	fn from_str(string: &str) -> Result<Self, DeserializeError> {
+		Self::deserialize_string(string)
+	}
+}
+
+#[derive(Clone)]
+pub struct Node {
+	pub parent: Option<usize>,
+	pub children: Vec<usize>,
+	// The child that redo follows
+	pub selected: Option<usize>,
+	// The actions taken from the parent to reach this node
+	pub actions: Vec<TimedAction>,
+	pub puzzle: Puzzle,
+	// Kept apart from the puzzle so the same position reached two ways
+	// is still one node
+	pub stats: Stats,
+	pub info_index: usize,
+	pub name: Option<String>,
+	pub annotation: Option<String>,
+}
+
 pub struct Replay {
-	current: Vec<Action>,
-	choices: HashMap<Puzzle, Vec<Action>>,
+	current: Vec<TimedAction>,
 	puzzle: Puzzle,
-	puzzle_history: Vec<Puzzle>,
+	stats: Stats,
 	info: Info,
-	info_history: Vec<usize>,
+	time: usize,
+	nodes: HashMap<usize, Node>,
+	next_id: usize,
+	path: Vec<usize>,
 }
 
 impl Replay {
-	pub fn new<F>(puzzle: Puzzle, event_handler: &mut F) -> Self
-    where   F: FnMut(&Event) {
-		let info = Info::new();
-		let index = info.index;
-		let mut replay = Self {
-			current: Vec::new(),
-			choices: HashMap::new(),
+	fn with_info(puzzle: Puzzle, info: Info) -> Self {
+		let root = Node {
+			parent: None,
+			children: Vec::new(),
+			selected: None,
+			actions: Vec::new(),
 			puzzle: puzzle.clone(),
-			puzzle_history: vec![puzzle],
-			info: info,
-			info_history: vec![index],
+			stats: Stats::default(),
+			info_index: info.index,
+			name: None,
+			annotation: None,
 		};
+		Self {
+			current: Vec::new(),
+			puzzle,
+			stats: Stats::default(),
+			info,
+			time: 0,
+			nodes: HashMap::from([(0, root)]),
+			next_id: 1,
+			path: vec![0],
+		}
+	}
+
+	pub fn new<F>(puzzle: Puzzle, event_handler: &mut F) -> Self
+    where   F: FnMut(&Event) {
+		let mut replay = Self::with_info(puzzle, Info::new());
 		replay.update(Action::Init, event_handler);
 		replay.new_frame();
 		replay
 	}
 
+	// Rebuilds a recorded line with a frame after each placement
+	pub fn from_log<F>(log: &TimedLog, event_handler: &mut F) -> Self
+    where   F: FnMut(&Event) {
+		let mut replay = Self::with_info(log.puzzle.clone(),
+			Info::from_values(log.info.clone()));
+		for action in log.actions.iter() {
+			replay.set_time(action.time);
+			replay.update(action.action, event_handler);
+			if let Action::Init | Action::PlacePiece = action.action {
+				replay.new_frame();
+			}
+		}
+		replay.new_frame();
+		replay
+	}
+
 	pub fn get_puzzle(&self) -> &Puzzle {
 		&self.puzzle
 	}
@@ -75,33 +220,132 @@ impl Replay {
 		self.puzzle.get_game()
 	}
 
+	pub fn get_stats(&self) -> &Stats {
+		&self.stats
+	}
+
 	pub fn get_frame(&self) -> usize {
-		self.puzzle_history.len() - 1
+		self.path.len() - 1
 	}
 
 	pub fn get_num_revealed(&self) -> usize {
 		self.info.index
 	}
 
+	pub fn set_time(&mut self, time: usize) {
+		self.time = time;
+	}
+
+	// Milliseconds from the player's first action to the last action on the
+	// current line, leaving out the wait before the first piece is touched
+	pub fn get_duration(&self) -> usize {
+		let mut times = self.path.iter()
+			.flat_map(|id| self.node(*id).actions.iter())
+			.chain(self.current.iter())
+			.skip_while(|a| matches!(a.action,
+				Action::Init | Action::SpawnPiece(_, _)))
+			.map(|a| a.time);
+		match times.next() {
+			Some(first) => times.last().unwrap_or(first) - first,
+			None => 0,
+		}
+	}
+
+	// The timed actions along the current line, enough to play it back
+	pub fn get_log(&self) -> TimedLog {
+		TimedLog {
+			puzzle: self.node(self.get_root()).puzzle.clone(),
+			info: self.info.info[..self.info.index].to_vec(),
+			actions: self.path.iter()
+				.flat_map(|id| self.node(*id).actions.iter())
+				.chain(self.current.iter())
+				.copied().collect(),
+		}
+	}
+
+	pub fn get_root(&self) -> usize {
+		self.path[0]
+	}
+
+	pub fn get_current_node(&self) -> usize {
+		*self.path.last()
+			.expect("there should be a previous state")
+	}
+
+	pub fn get_path(&self) -> &[usize] {
+		&self.path
+	}
+
+	pub fn get_node(&self, id: usize) -> Option<&Node> {
+		self.nodes.get(&id)
+	}
+
+	pub fn get_children(&self, id: usize) -> &[usize] {
+		self.nodes.get(&id).map_or(&[], |n| &n.children)
+	}
+
+	fn node(&self, id: usize) -> &Node {
+		self.nodes.get(&id).expect("node should exist")
+	}
+
+	fn node_mut(&mut self, id: usize) -> &mut Node {
+		self.nodes.get_mut(&id).expect("node should exist")
+	}
+
+	pub fn set_name(&mut self, id: usize, name: Option<String>) {
+		if let Some(node) = self.nodes.get_mut(&id) {
+			node.name = name;
+		}
+	}
+
+	pub fn set_annotation(&mut self, id: usize, annotation: Option<String>) {
+		if let Some(node) = self.nodes.get_mut(&id) {
+			node.annotation = annotation;
+		}
+	}
+
 	pub fn revert(&mut self) {
 		self.current.clear();
-		self.puzzle = self.puzzle_history.last()
-			.expect("there should be a previous state")
-			.clone();
+		let node = &self.nodes[&self.get_current_node()];
+		self.puzzle = node.puzzle.clone();
+		self.stats = node.stats.clone();
 	}
 
 	pub fn new_frame(&mut self) {
 		if self.current.len() == 0 {
 			return;
 		}
-		let puzzle = self.puzzle_history.last()
-			.expect("there should be a previous state")
-			.clone();
-		let choice = std::mem::replace(
+		let parent = self.get_current_node();
+		let actions = std::mem::replace(
 			&mut self.current, Vec::new());
-		self.choices.insert(puzzle, choice);
-		self.puzzle_history.push(self.puzzle.clone());
-		self.info_history.push(self.info.index);
+		// Playing the same line again revisits its node
+		let existing = self.node(parent).children.iter()
+			.find(|c| self.node(**c).puzzle == self.puzzle).copied();
+		let id = match existing {
+			Some(id) => {
+				self.node_mut(id).actions = actions;
+				id
+			}
+			None => {
+				let id = self.next_id;
+				self.next_id += 1;
+				self.nodes.insert(id, Node {
+					parent: Some(parent),
+					children: Vec::new(),
+					selected: None,
+					actions,
+					puzzle: self.puzzle.clone(),
+					stats: self.stats.clone(),
+					info_index: self.info.index,
+					name: None,
+					annotation: None,
+				});
+				self.node_mut(parent).children.push(id);
+				id
+			}
+		};
+		self.node_mut(parent).selected = Some(id);
+		self.path.push(id);
 	}
 
 	pub fn undo(&mut self) {
@@ -109,70 +353,223 @@ impl Replay {
 			return;
 		}
 		self.current.clear();
-		self.puzzle_history.pop();
-		self.puzzle = self.puzzle_history.last()
-			.expect("there should be a previous state")
-			.clone();
-		self.info_history.pop();
-		self.info.index = self.info_history.last()
-			.expect("there should be a previous state")
-			.clone();
+		self.path.pop();
+		let node = &self.nodes[&self.get_current_node()];
+		self.puzzle = node.puzzle.clone();
+		self.stats = node.stats.clone();
+		self.info.index = node.info_index;
 	}
 
 	pub fn redo<F>(&mut self, event_handler: &mut F) -> bool
     where   F: FnMut(&Event) {
-		let puzzle = self.puzzle_history.last()
-			.expect("there should be a previous state")
-			.clone();
-		if let Some(choice) = self.choices.get(&puzzle) {
-			self.current.clear();
-			self.puzzle = puzzle;
-			for action in choice.iter() {
-				self.puzzle.update(*action, &mut self.info, event_handler);
+		let node = self.node(self.get_current_node());
+		match node.selected.or(node.children.first().copied()) {
+			Some(child) => self.switch_branch(child, event_handler),
+			None => false,
+		}
+	}
+
+	// Steps forward into one of the current node's children
+	pub fn switch_branch<F>(&mut self, child: usize, event_handler: &mut F) -> bool
+    where   F: FnMut(&Event) {
+		let parent = self.get_current_node();
+		if !self.node(parent).children.contains(&child) {
+			return false;
+		}
+		self.current.clear();
+		self.puzzle = self.node(parent).puzzle.clone();
+		self.stats = self.node(parent).stats.clone();
+		self.info.index = self.node(parent).info_index;
+		let actions = self.node(child).actions.clone();
+		for action in actions.iter() {
+			self.apply(action.action, event_handler);
+		}
+		self.node_mut(parent).selected = Some(child);
+		self.path.push(child);
+		true
+	}
+
+	// Jumps to any node, making its line the one that redo follows
+	pub fn goto(&mut self, id: usize) -> bool {
+		if !self.nodes.contains_key(&id) {
+			return false;
+		}
+		let mut path = vec![id];
+		while let Some(parent) = self.node(*path.last()
+				.expect("path should not be empty")).parent {
+			self.node_mut(parent).selected = Some(path[path.len() - 1]);
+			path.push(parent);
+		}
+		path.reverse();
+		self.path = path;
+		self.current.clear();
+		let node = &self.nodes[&id];
+		self.puzzle = node.puzzle.clone();
+		self.stats = node.stats.clone();
+		self.info.index = node.info_index;
+		true
+	}
+
+	// Removes a node and everything after it, leaving the root in place
+	pub fn delete_branch(&mut self, id: usize) -> bool {
+		let parent = match self.nodes.get(&id).and_then(|n| n.parent) {
+			Some(parent) => parent,
+			None => return false,
+		};
+		if self.path.contains(&id) {
+			self.goto(parent);
+		}
+		let mut removed = vec![id];
+		while let Some(id) = removed.pop() {
+			if let Some(node) = self.nodes.remove(&id) {
+				removed.extend(node.children);
 			}
-			self.puzzle_history.push(self.puzzle.clone());
-			self.info_history.push(self.info.index);
-			return true;
 		}
-		false
+		let parent = self.node_mut(parent);
+		parent.children.retain(|c| *c != id);
+		if parent.selected == Some(id) {
+			parent.selected = parent.children.first().copied();
+		}
+		true
+	}
+
+	fn apply<F>(&mut self, action: Action, event_handler: &mut F)
+    where   F: FnMut(&Event) {
+		let stats = &mut self.stats;
+		let rules = self.puzzle.spin_rules;
+		let attack_table = self.puzzle.attack_table();
+		self.puzzle.update(action, &mut self.info, &mut |event| {
+			event_handler(event);
+			stats.handle_event(event, &rules, &attack_table);
+		});
 	}
 
 	pub fn update<F>(&mut self, action: Action, event_handler: &mut F)
     where   F: FnMut(&Event) {
-		self.current.push(action);
-		let clears = self.puzzle.update(action, &mut self.info, event_handler);
-		let index = self.info_history.last()
-			.expect("there should be a previous state")
-			.clone();
+		self.current.push(TimedAction { time: self.time, action });
+		self.apply(action, event_handler);
+		let index = self.node(self.get_current_node()).info_index;
 		if self.info.index != index {
 			self.new_frame();
 		}
-		clears
 	}
 
 	pub fn reroll_forward(&mut self, forward: usize) {
 		self.info.info[self.info.index + forward] += 1;
 	}
 
-	pub fn reroll_backward<F>(&mut self, backward: usize, event_handler: &mut F)
-    where   F: FnMut(&Event) {
-		if backward > self.info.index {
-			return;
-		}
-		let target_index = self.info.index - backward;
+	// Undoes back to a random value, changes it, and replays the frames after
+	fn reroll<F, G>(&mut self, target_index: usize, reroll: G, event_handler: &mut F)
+    where   F: FnMut(&Event),
+            G: FnOnce(&mut Self) {
 		let mut choices = Vec::new();
 		while self.info.index > target_index {
+			let node = self.get_current_node();
 			self.undo();
-			choices.push(self.choices.get(&self.puzzle)
-				.expect("should have saved actions")
-				.clone());
+			choices.push(self.node(node).actions.clone());
 		}
-		self.reroll_forward(target_index - self.info.index);
+		reroll(self);
 		for choice in choices.iter().rev() {
 			for action in choice.iter() {
-				self.update(*action, event_handler);
+				self.update(action.action, event_handler);
 			}
 			self.new_frame();
 		}
 	}
-}
\ No newline at end of file
+
+	pub fn reroll_backward<F>(&mut self, backward: usize, event_handler: &mut F)
+    where   F: FnMut(&Event) {
+		if backward > self.info.index {
+			return;
+		}
+		let target_index = self.info.index - backward;
+		self.reroll(target_index, |replay| replay.reroll_forward(
+			target_index - replay.info.index), event_handler);
+	}
+
+	// The bag as it was when the random value at an index was drawn
+	fn rando_at(&self, index: usize) -> Option<BagRandomizer> {
+		let node = self.path.iter().rev().map(|id| self.node(*id))
+			.find(|n| n.info_index <= index)?;
+		let mut rando = node.puzzle.game.queue.rando.clone();
+		let mut info = Info::from_values(self.info.info.clone());
+		info.index = node.info_index;
+		while info.index < index {
+			rando.next(&mut info);
+		}
+		Some(rando)
+	}
+
+	pub fn reroll_options(&self, backward: usize) -> EnumSet<PieceType> {
+		if backward == 0 || backward > self.info.index {
+			return EnumSet::new();
+		}
+		self.rando_at(self.info.index - backward)
+			.map_or(EnumSet::new(), |r| r.set)
+	}
+
+	pub fn reroll_to<F>(&mut self, backward: usize, piece: PieceType,
+			event_handler: &mut F) -> bool
+    where   F: FnMut(&Event) {
+		if backward == 0 || backward > self.info.index {
+			return false;
+		}
+		let target_index = self.info.index - backward;
+		let options: Vec<PieceType> = match self.rando_at(target_index) {
+			Some(rando) => rando.options().collect(),
+			None => return false,
+		};
+		let choice = match options.iter().position(|p| *p == piece) {
+			Some(choice) => choice as u32,
+			None => return false,
+		};
+		if *self.info.choice_at(target_index, &options) == piece {
+			return true;
+		}
+		self.reroll(target_index, |replay|
+			replay.info.info[target_index] = choice, event_handler);
+		true
+	}
+}
+
+// Re-feeds a timed log into its puzzle as time passes
+pub struct Playback {
+	puzzle: Puzzle,
+	info: Info,
+	actions: Vec<TimedAction>,
+	index: usize,
+}
+
+impl Playback {
+	pub fn new(log: &TimedLog) -> Self {
+		Self {
+			puzzle: log.puzzle.clone(),
+			info: Info::from_values(log.info.clone()),
+			actions: log.actions.clone(),
+			index: 0,
+		}
+	}
+
+	pub fn get_puzzle(&self) -> &Puzzle {
+		&self.puzzle
+	}
+
+	pub fn get_duration(&self) -> usize {
+		self.actions.last().map_or(0, |a| a.time)
+	}
+
+	pub fn is_finished(&self) -> bool {
+		self.index >= self.actions.len()
+	}
+
+	pub fn advance<F>(&mut self, time: usize, event_handler: &mut F)
+    where   F: FnMut(&Event) {
+		while let Some(action) = self.actions.get(self.index) {
+			if action.time > time {
+				break;
+			}
+			self.puzzle.update(action.action, &mut self.info, event_handler);
+			self.index += 1;
+		}
+	}
+}
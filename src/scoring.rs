@@ -0,0 +1,158 @@
+use crate::condition::all_clear::AllClearType;
+use crate::condition::chain::ChainClear;
+use crate::condition::chain::ChainEvent;
+use crate::condition::chain::ChainHandler;
+use crate::condition::spin::SpinHandler;
+use crate::condition::spin::SpinRules;
+use crate::condition::spin::SpinType;
+use crate::game::game::Event;
+use crate::position::Vector;
+use crate::serialize::DeserializeError;
+use crate::serialize::SerializeUrlSafe;
+
+pub const SOFT_DROP_POINTS: usize = 1;
+pub const HARD_DROP_POINTS: usize = 2;
+pub const COMBO_POINTS: usize = 50;
+// Taken off a puzzle's score for each hint asked for
+pub const HINT_PENALTY: usize = 200;
+
+pub fn clear_points(chain_clear: &ChainClear, level: usize) -> usize {
+    let spin_clear = chain_clear.clear;
+    let clear = spin_clear.clear;
+    let points = match (&spin_clear.spin, clear.lines) {
+        (Some(SpinType::Full), 0) => 400,
+        (Some(SpinType::Full), 1) => 800,
+        (Some(SpinType::Full), 2) => 1200,
+        (Some(SpinType::Full), _) => 1600,
+        (Some(SpinType::Mini), 0) => 100,
+        (Some(SpinType::Mini), 1) => 200,
+        (Some(SpinType::Mini), _) => 400,
+        (None, 0) => 0,
+        (None, 1) => 100,
+        (None, 2) => 300,
+        (None, 3) => 500,
+        (None, _) => 800,
+    };
+    let points = match chain_clear.b2b {
+        true => points * 3 / 2,
+        false => points,
+    };
+    if clear.lines == 0 {
+        return points * level;
+    }
+    let combo_points = COMBO_POINTS * chain_clear.combo.saturating_sub(1);
+    let all_clear_points = if AllClearType::from_line_clear(clear)
+            == AllClearType::ALL_CLEAR {
+        match clear.lines {
+            1 => 800,
+            2 => 1200,
+            3 => 1800,
+            _ => match chain_clear.b2b {
+                true => 3200,
+                false => 2000,
+            },
+        }
+    } else {
+        0
+    };
+    (points + combo_points + all_clear_points) * level
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct Scoring {
+    pub score: usize,
+    pub level: usize,
+    // Hints asked for, each taking HINT_PENALTY off the total
+    pub hints: usize,
+    spin_handler: SpinHandler,
+    chain_handler: ChainHandler,
+}
+
+impl Scoring {
+    pub fn new(level: usize) -> Self {
+        Self {
+            score: 0,
+            level,
+            hints: 0,
+            spin_handler: SpinHandler::new(None),
+            chain_handler: ChainHandler::new(false, 0),
+        }
+    }
+
+    // The score with the hint penalty taken off
+    pub fn total(&self) -> usize {
+        self.score.saturating_sub(HINT_PENALTY * self.hints)
+    }
+
+    // The back-to-back and combo going into the next clear
+    pub fn get_chain(&self) -> &ChainHandler {
+        &self.chain_handler
+    }
+
+    pub fn handle_event(&mut self, event: &Event, rules: &SpinRules) {
+        match event {
+            Event::Move(vec) if *vec == Vector::ONE_DOWN =>
+                self.score += SOFT_DROP_POINTS,
+            Event::Place(drop) =>
+                self.score += HARD_DROP_POINTS * drop,
+            Event::Hint => self.hints += 1,
+            _ => (),
+        }
+        if let Some(spin_event) = self.spin_handler.handle_spin(event, rules) {
+            if let Some(ChainEvent::LineClear(chain_clear))
+                    = self.chain_handler.handle_no_zone(&spin_event) {
+                self.score += clear_points(&chain_clear, self.level);
+            }
+        }
+    }
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+// Tagged like metadata, with only the fields that differ from a fresh score
+impl SerializeUrlSafe for Scoring {
+    fn serialize(&self) -> String {
+        let fresh = Self::new(self.level);
+        let mut string = String::new();
+        if self.score != fresh.score {
+            string.push('S');
+            string.push_str(&self.score.serialize());
+        }
+        if self.level != Self::default().level {
+            string.push('L');
+            string.push_str(&self.level.serialize());
+        }
+        if self.hints != fresh.hints {
+            string.push('H');
+            string.push_str(&self.hints.serialize());
+        }
+        if self.spin_handler != fresh.spin_handler {
+            string.push('K');
+            string.push_str(&self.spin_handler.serialize());
+        }
+        if self.chain_handler != fresh.chain_handler {
+            string.push('C');
+            string.push_str(&self.chain_handler.serialize());
+        }
+        string + "."
+    }
+
+    fn deserialize(input: &mut crate::serialize::DeserializeInput) -> Result<Self, crate::serialize::DeserializeError> {
+        let mut scoring = Self::default();
+        while !input.next_if('.')? {
+            match input.next()? {
+                'S' => scoring.score = usize::deserialize(input)?,
+                'L' => scoring.level = usize::deserialize(input)?,
+                'H' => scoring.hints = usize::deserialize(input)?,
+                'K' => scoring.spin_handler = SpinHandler::deserialize(input)?,
+                'C' => scoring.chain_handler = ChainHandler::deserialize(input)?,
+                _ => return Err(DeserializeError::new("Scoring fields should be tagged with S, L, H, K, or C.")),
+            }
+        }
+        Ok(scoring)
+    }
+}
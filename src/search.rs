@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use crate::board_analysis::column_heights;
+use crate::condition::spin::SpinRules;
+use crate::condition::spin::SpinType;
+use crate::game::Action;
+use crate::game::ActivePiece;
+use crate::game::Board;
+use crate::game::Game;
+use crate::game::MaybeActive;
+use crate::game::game::LineClear;
+use crate::position::Position;
+use crate::position::Rotation;
+use crate::position::Vector;
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct Placement {
+    // Where the piece lands, already dropped
+    pub piece: ActivePiece,
+    // The kick used if the last input was a rotation, for spin detection
+    pub kick: Option<usize>,
+    // Inputs from spawn, not including the final PlacePiece
+    pub actions: Vec<Action>,
+}
+
+impl Placement {
+    // The cells covered, sorted so equal shapes compare equal
+    pub fn cells(&self) -> [Position; 4] {
+        let mut cells = self.piece.get_mino_positions();
+        cells.sort_by_key(|p| (p.y, p.x));
+        cells
+    }
+
+    // Finished with a rotation into a spot it can't slide or lift out of
+    pub fn is_spin(&self, board: &Board) -> bool {
+        self.kick.is_some() && [Vector::ONE_LEFT, Vector::ONE_RIGHT,
+            Vector::ONE_UP].iter()
+            .all(|v| !self.piece.clone().try_move(board, *v))
+    }
+
+    // The spin the rules would count this landing as, if any
+    pub fn spin(&self, board: &Board, rules: &SpinRules) -> Option<SpinType> {
+        let kick = self.kick?;
+        let mut after = board.clone();
+        self.piece.clone().place(&mut after);
+        let clear = LineClear {
+            lines: after.clear_lines(),
+            active: self.piece.clone(),
+            board: board.clone(),
+            in_zone: false,
+        };
+        rules.detect(&clear, kick)
+    }
+
+    // Slid under an overhang rather than dropped straight in from above
+    pub fn is_tuck(&self, board: &Board) -> bool {
+        let heights = column_heights(board);
+        let top = self.piece.get_mino_positions().iter()
+            .map(|p| heights[p.x as usize]).max().unwrap_or(0);
+        let mut piece = self.piece.clone();
+        while piece.get_mino_positions().iter().any(|p| (p.y as usize) < top) {
+            if !piece.try_move(board, Vector::ONE_UP) {
+                return !self.is_spin(board);
+            }
+        }
+        false
+    }
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct Move {
+    pub hold: bool,
+    pub placement: Placement,
+    // Everything from spawning or holding through to PlacePiece
+    pub actions: Vec<Action>,
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+struct State {
+    piece: ActivePiece,
+    kick: Option<usize>,
+}
+
+const INPUTS: [Action; 6] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::MoveDown,
+    Action::Rotate(Rotation::Clockwise),
+    Action::Rotate(Rotation::Anticlockwise),
+    Action::Rotate(Rotation::Flip),
+];
+
+fn apply(board: &Board, state: &State, action: Action) -> Option<State> {
+    let mut piece = state.piece.clone();
+    let kick = match action {
+        Action::MoveLeft => piece.try_move(board, Vector::ONE_LEFT)
+            .then_some(None)?,
+        Action::MoveRight => piece.try_move(board, Vector::ONE_RIGHT)
+            .then_some(None)?,
+        Action::MoveDown => piece.try_move(board, Vector::ONE_DOWN)
+            .then_some(None)?,
+        Action::Rotate(rot) => Some(piece.try_rotate(board, rot)?),
+        _ => return None,
+    };
+    Some(State { piece, kick })
+}
+
+// Every distinct landing reachable from any of starts with the fewest
+// inputs, along with the index of the start it's reached from. A hard drop
+// keeps the last kick, so with by_kick each kick that can end in the same
+// spot is its own placement. Without it only the cells matter, which leaves
+// far fewer states to search.
+pub fn reachable_placements(board: &Board, starts: &[ActivePiece],
+        by_kick: bool) -> Vec<(usize, Placement)> {
+    let key = |state: &State| State {
+        piece: state.piece.clone(),
+        kick: state.kick.filter(|_| by_kick),
+    };
+    let mut parents: HashMap<State, Option<(State, Action)>> = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        let start = State { piece: start.clone(), kick: None };
+        if let Entry::Vacant(entry) = parents.entry(key(&start)) {
+            entry.insert(None);
+            queue.push_back(start);
+        }
+    }
+
+    let mut landings = HashSet::new();
+    let mut placements = Vec::new();
+    while let Some(state) = queue.pop_front() {
+        let ghost = state.piece.get_ghost(board);
+        let placement = Placement {
+            piece: ghost,
+            kick: state.kick,
+            actions: Vec::new(),
+        };
+        let landing = (placement.cells(), placement.kick.filter(|_| by_kick));
+        if landings.insert(landing) {
+            placements.push((placement, state.clone()));
+        }
+        for action in INPUTS {
+            if let Some(next) = apply(board, &state, action) {
+                if let Entry::Vacant(entry) = parents.entry(key(&next)) {
+                    entry.insert(Some((state.clone(), action)));
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    placements.into_iter().map(|(mut placement, state)| {
+        let mut actions = Vec::new();
+        let mut current = state;
+        while let Some(Some((parent, action))) = parents.get(&key(&current)) {
+            actions.push(*action);
+            current = parent.clone();
+        }
+        actions.reverse();
+        placement.actions = actions;
+        let start = starts.iter().position(|s| *s == current.piece)
+            .unwrap_or(0);
+        (start, placement)
+    }).collect()
+}
+
+// Rotations a piece can be spawned in by holding a rotate key
+const IRS: [Rotation; 4] = [
+    Rotation::Zero,
+    Rotation::Clockwise,
+    Rotation::Anticlockwise,
+    Rotation::Flip,
+];
+
+// Every placement of the current piece and of the piece hold would swap in.
+// Spawning rotated can reach spots a normal spawn can't on a tall stack.
+pub fn moves(game: &Game, by_kick: bool) -> Vec<Move> {
+    if game.over {
+        return Vec::new();
+    }
+    let swap = game.hold.or_else(|| game.queue.pieces.front().copied());
+    let mut starts = Vec::new();
+    match &game.piece {
+        Some(MaybeActive::Active(piece)) => {
+            starts.push((false, piece.clone(), Vec::new()));
+            if !game.has_held {
+                for irs in IRS {
+                    if let Some(start) = swap.and_then(|piece_type|
+                            ActivePiece::spawn(&game.board, piece_type, irs)) {
+                        starts.push((true, start,
+                            vec![Action::HoldPiece(irs)]));
+                    }
+                }
+            }
+        }
+        Some(MaybeActive::Inactive(current)) => {
+            for irs in IRS {
+                if let Some(start) = ActivePiece::spawn(
+                        &game.board, *current, irs) {
+                    starts.push((false, start,
+                        vec![Action::SpawnPiece(irs, false)]));
+                }
+            }
+            if swap != Some(*current) {
+                for irs in IRS {
+                    if let Some(start) = swap.and_then(|piece_type|
+                            ActivePiece::spawn(&game.board, piece_type, irs)) {
+                        starts.push((true, start,
+                            vec![Action::SpawnPiece(irs, true)]));
+                    }
+                }
+            }
+        }
+        None => (),
+    }
+
+    // Each spawn rotation is searched together, since most of what one can
+    // reach the others can too
+    let mut moves = Vec::new();
+    for hold in [false, true] {
+        let (pieces, prefixes): (Vec<ActivePiece>, Vec<Vec<Action>>) = starts
+            .iter()
+            .filter(|(h, _, _)| *h == hold)
+            .map(|(_, start, prefix)| (start.clone(), prefix.clone()))
+            .unzip();
+        for (start, placement) in reachable_placements(&game.board, &pieces,
+                by_kick) {
+            let mut actions = prefixes[start].clone();
+            actions.extend(placement.actions.iter().copied());
+            actions.push(Action::PlacePiece);
+            moves.push(Move { hold, placement, actions });
+        }
+    }
+    moves
+}
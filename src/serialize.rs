@@ -235,4 +235,15 @@ impl SerializeUrlSafe for i32 {
 		Ok(isize::deserialize(input)?.try_into()
 			.map_err(|_| DeserializeError::new("Base64 was too large to fit in an integer."))?)
 	}
+}
+
+impl SerializeUrlSafe for u32 {
+	fn serialize(&self) -> String {
+		(*self as usize).serialize()
+	}
+
+	fn deserialize(input: &mut DeserializeInput) -> Result<Self, DeserializeError> {
+		usize::deserialize(input)?.try_into()
+			.map_err(|_| DeserializeError::new("Base64 was too large to fit in an integer."))
+	}
 }
\ No newline at end of file
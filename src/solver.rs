@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::board_analysis::column_heights;
+use crate::game::Action;
+use crate::game::Board;
+use crate::game::Game;
+use crate::position::Position;
+use crate::puzzle::Puzzle;
+use crate::replay::Info;
+use crate::replay::Replay;
+use crate::search;
+use crate::search::Move;
+use crate::search::Placement;
+use crate::serialize::SerializeUrlSafe;
+
+const DEFAULT_MAX_NODES: usize = 5000;
+// Searches run on the page's thread, so they give up after this long
+// wherever a clock has been set
+const DEFAULT_TIME_LIMIT: Duration = Duration::from_millis(250);
+// How many positions to search between looking at the clock
+const CLOCK_INTERVAL: usize = 64;
+// Seeds the pieces drawn past the queue
+const INFO_SEED: u64 = 0;
+
+// The time since some fixed point. std can't read the time in the browser,
+// so whatever runs the solver there has to supply it.
+static CLOCK: OnceLock<fn() -> Duration> = OnceLock::new();
+
+// Lets searches be capped by time as well as by positions searched
+pub fn set_clock(clock: fn() -> Duration) {
+    let _ = CLOCK.set(clock);
+}
+
+// Looks for a way to meet the win conditions using only the pieces the
+// player can already see
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct Solver {
+    // Positions searched before giving up
+    pub max_nodes: usize,
+    // How long to search before giving up, if there's a clock
+    pub time_limit: Duration,
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct Solution {
+    pub moves: Vec<Move>,
+    // The board each move was made on
+    pub boards: Vec<Board>,
+    // How the puzzle looks once it's won
+    pub puzzle: Puzzle,
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct SolveReport {
+    pub solutions: Vec<Solution>,
+    // Positions expanded and the moves found from them
+    pub nodes: usize,
+    pub branches: usize,
+    // Whether every position was searched, so no solutions were missed
+    pub complete: bool,
+}
+
+impl Solution {
+    // Plays the moves out from the start of the puzzle, so the line can be
+    // watched or checked
+    pub fn replay(&self, puzzle: &Puzzle) -> Replay {
+        let mut replay = Replay::new(puzzle.clone(), &mut |_| ());
+        for m in self.moves.iter() {
+            for action in m.actions.iter() {
+                replay.update(*action, &mut |_| ());
+            }
+        }
+        replay
+    }
+}
+
+impl SolveReport {
+    // Average moves available from each position searched
+    pub fn branching_factor(&self) -> f64 {
+        self.branches as f64 / self.nodes.max(1) as f64
+    }
+}
+
+struct Search<F> {
+    is_new: F,
+    // Whether landing the same cells with a different kick can score
+    // differently, so both need searching
+    by_kick: bool,
+    // When to give up, if there's a clock
+    deadline: Option<Duration>,
+    // Whether solutions are told apart by the placements they make, so a
+    // position reached with different placements can lead somewhere new
+    distinct: bool,
+    // Cells of the authored hints and their order, tried before anything
+    // else with the earliest first
+    hinted: HashMap<[Position; 4], usize>,
+    info: Info,
+    visited: HashSet<(String, Vec<[Position; 4]>)>,
+    path: Vec<Move>,
+    boards: Vec<Board>,
+    report: SolveReport,
+}
+
+impl Solver {
+    pub fn new(max_nodes: usize) -> Self {
+        Self { max_nodes, time_limit: DEFAULT_TIME_LIMIT }
+    }
+
+    // The moves from here to a win, if one was found within the limits
+    pub fn solve(&self, puzzle: &Puzzle) -> Option<Vec<Move>> {
+        self.solutions(puzzle, 1).solutions.into_iter().next()
+            .map(|solution| solution.moves)
+    }
+
+    // Up to max_solutions winning lines, along with how the search went
+    pub fn solutions(&self, puzzle: &Puzzle, max_solutions: usize)
+            -> SolveReport {
+        self.search(puzzle, max_solutions, |_| true, false)
+    }
+
+    // Like solutions, but only counting the ones is_new accepts, which can
+    // tell solutions apart by their placements
+    pub fn distinct_solutions<F>(&self, puzzle: &Puzzle,
+            max_solutions: usize, is_new: F) -> SolveReport
+    where   F: FnMut(&Solution) -> bool {
+        self.search(puzzle, max_solutions, is_new, true)
+    }
+
+    fn search<F>(&self, puzzle: &Puzzle, max_solutions: usize, is_new: F,
+            distinct: bool) -> SolveReport
+    where   F: FnMut(&Solution) -> bool {
+        let mut root = puzzle.clone();
+        let mut info = Info::seeded(INFO_SEED);
+        // Pieces past the queue are random, so stop refilling it and treat
+        // anything drawn once it's empty as unknown
+        root.game.queue.length = 0;
+        // Puzzles straight from a link or generator haven't drawn a piece
+        if root.game.piece.is_none() && root.game.queue.fill() > 0 {
+            root.update(Action::Init, &mut info, &mut |_| ());
+        }
+        let mut search = Search {
+            is_new,
+            by_kick: root.win_conditions.has_spin_context(),
+            deadline: CLOCK.get().map(|clock| clock() + self.time_limit),
+            distinct,
+            hinted: puzzle.metadata.hints.iter().enumerate().rev()
+                .map(|(i, hint)| {
+                    let mut cells = hint.get_mino_positions();
+                    cells.sort_by_key(|p| (p.y, p.x));
+                    (cells, i)
+                }).collect(),
+            info,
+            visited: HashSet::new(),
+            path: Vec::new(),
+            boards: Vec::new(),
+            report: SolveReport {
+                solutions: Vec::new(),
+                nodes: 0,
+                branches: 0,
+                complete: true,
+            },
+        };
+        if max_solutions > 0 {
+            self.visit(&mut search, &root, false, max_solutions);
+        }
+        search.report
+    }
+
+    // Records a win, returning whether to keep searching
+    fn found<F>(search: &mut Search<F>, puzzle: &Puzzle,
+            max_solutions: usize) -> bool
+    where   F: FnMut(&Solution) -> bool {
+        let solution = Solution {
+            moves: search.path.clone(),
+            boards: search.boards.clone(),
+            puzzle: puzzle.clone(),
+        };
+        if !(search.is_new)(&solution) {
+            return true;
+        }
+        search.report.solutions.push(solution);
+        search.report.solutions.len() < max_solutions
+    }
+
+    // Depth first over placements, trying authored hints and then the
+    // lowest and cleanest first.
+    // Once the current piece is unknown, only a swap for the known hold
+    // piece is allowed. Returns whether to keep searching.
+    fn visit<F>(&self, search: &mut Search<F>, puzzle: &Puzzle,
+            unknown: bool, max_solutions: usize) -> bool
+    where   F: FnMut(&Solution) -> bool {
+        if puzzle.won {
+            return Self::found(search, puzzle, max_solutions);
+        }
+        // Zone puzzles are won when the zone ends, which can happen after
+        // any placement
+        if puzzle.game.in_zone && !search.path.is_empty() {
+            let mut ended = puzzle.clone();
+            ended.update(Action::ToggleZone, &mut search.info, &mut |_| ());
+            if ended.won {
+                let last = search.path.len() - 1;
+                search.path[last].actions.push(Action::ToggleZone);
+                let keep_going = Self::found(search, &ended, max_solutions);
+                search.path[last].actions.pop();
+                if !keep_going {
+                    // Staying in the zone is still to be searched
+                    if !puzzle.over {
+                        search.report.complete = false;
+                    }
+                    return false;
+                }
+            }
+        }
+        let placements = match search.distinct {
+            true => placement_set(&search.path),
+            false => Vec::new(),
+        };
+        if puzzle.over
+                || !search.visited.insert((state_key(puzzle), placements)) {
+            return true;
+        }
+        if search.report.nodes >= self.max_nodes || out_of_time(search) {
+            search.report.complete = false;
+            return false;
+        }
+        search.report.nodes += 1;
+
+        let mut children = search::moves(&puzzle.game, search.by_kick)
+            .into_iter()
+            .filter(|m| !unknown || (m.hold && puzzle.game.hold.is_some()))
+            .map(|m| match search.hinted.get(&m.placement.cells()) {
+                Some(i) => (i64::MAX - *i as i64, m),
+                None => (order_score(&puzzle.game, &m.placement), m),
+            })
+            .collect::<Vec<(i64, Move)>>();
+        search.report.branches += children.len();
+        children.sort_by(|(a, _), (b, _)| b.cmp(a));
+        let count = children.len();
+        for (i, (_, m)) in children.into_iter().enumerate() {
+            let mut child = puzzle.clone();
+            let unknown = play_move(&mut child, &m, &mut search.info);
+            search.path.push(m);
+            search.boards.push(puzzle.game.board.clone());
+            let keep_going = self.visit(search, &child, unknown,
+                max_solutions);
+            search.path.pop();
+            search.boards.pop();
+            if !keep_going {
+                if i + 1 < count {
+                    search.report.complete = false;
+                }
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_NODES)
+    }
+}
+
+// What the rest of the search depends on. Score is left out, so lines that
+// reach the same position with different points are searched once.
+fn state_key(puzzle: &Puzzle) -> String {
+    format! {"{}{}{}",
+        puzzle.game.serialize(),
+        puzzle.win_conditions.serialize(),
+        puzzle.end_conditions.serialize(),
+    }
+}
+
+// The cells each move covered, in an order that ignores the order played
+pub fn placement_set(moves: &[Move]) -> Vec<[Position; 4]> {
+    let mut placements = moves.iter()
+        .map(|m| m.placement.cells())
+        .collect::<Vec<[Position; 4]>>();
+    placements.sort_by_key(|cells| cells.map(|p| (p.y, p.x)));
+    placements
+}
+
+fn out_of_time<F>(search: &Search<F>) -> bool {
+    search.report.nodes.is_multiple_of(CLOCK_INTERVAL)
+        && search.deadline.zip(CLOCK.get())
+            .is_some_and(|(deadline, clock)| clock() >= deadline)
+}
+
+// Orders moves by the height and holes they leave. Runs for every move
+// from every position, so it skips the bot's fuller evaluation.
+fn order_score(game: &Game, placement: &Placement) -> i64 {
+    let mut board = game.board.clone();
+    placement.piece.clone().place(&mut board);
+    match game.in_zone {
+        true => board.clear_lines_zone(),
+        false => board.clear_lines(),
+    };
+    let height = column_heights(&board).iter().copied().max().unwrap_or(0);
+    -(height as i64) - 4 * board.holes() as i64
+}
+
+// Plays a move on a puzzle whose queue no longer refills, returning whether
+// the next piece had to come from past the queue
+fn play_move(puzzle: &mut Puzzle, m: &Move, info: &mut Info) -> bool {
+    let unknown = puzzle.game.queue.pieces.is_empty();
+    for action in m.actions.iter() {
+        puzzle.update(*action, info, &mut |_| ());
+    }
+    unknown
+}
@@ -0,0 +1,317 @@
+use std::collections::HashSet;
+
+use crate::condition::attack::AttackTable;
+use crate::condition::chain::ChainEvent;
+use crate::condition::chain::ChainHandler;
+use crate::condition::spin::SpinEvent;
+use crate::condition::spin::SpinHandler;
+use crate::condition::spin::SpinRules;
+use crate::condition::spin::SpinType;
+use crate::game::Action;
+use crate::game::ActivePiece;
+use crate::game::Board;
+use crate::game::PieceType;
+use crate::game::game::Event;
+use crate::game::game::LineClear;
+use crate::position::Position;
+use crate::position::Rotation;
+use crate::position::Vector;
+use crate::serialize::SerializeUrlSafe;
+
+use enumset::EnumSet;
+
+// A move or rotation of the current piece that went through
+#[derive(Hash, Eq, PartialEq, Copy, Clone)]
+enum PieceInput {
+    Move(Vector),
+    // The rotation and the index of the kick it used
+    Rotate(Rotation, usize),
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct Stats {
+    pub pieces: usize,
+    pub lines: usize,
+    pub attack: usize,
+    pub holds: usize,
+    pub zones: usize,
+    pub zone_lines: usize,
+    pub max_zone: usize,
+    // Indexed by PieceType
+    pub spins: [usize; 7],
+    pub mini_spins: [usize; 7],
+    pub b2b: usize,
+    pub max_b2b: usize,
+    // Counted from the second clear in a row, like the combo points
+    pub max_combo: usize,
+    pub finesse_faults: usize,
+    inputs: Vec<PieceInput>,
+    rotating: Rotation,
+    drop: usize,
+    soft_dropped: bool,
+    spin_handler: SpinHandler,
+    chain_handler: ChainHandler,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self {
+            pieces: 0,
+            lines: 0,
+            attack: 0,
+            holds: 0,
+            zones: 0,
+            zone_lines: 0,
+            max_zone: 0,
+            spins: [0; 7],
+            mini_spins: [0; 7],
+            b2b: 0,
+            max_b2b: 0,
+            max_combo: 0,
+            finesse_faults: 0,
+            inputs: Vec::new(),
+            rotating: Rotation::Zero,
+            drop: 0,
+            soft_dropped: false,
+            spin_handler: SpinHandler::new(None),
+            chain_handler: ChainHandler::new(false, 0),
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &Event, rules: &SpinRules,
+            attack_table: &AttackTable) {
+        match event {
+            Event::Spawn => self.reset_inputs(),
+            Event::Hold => self.holds += 1,
+            Event::ZoneStart => self.zones += 1,
+            &Event::Input(Action::Rotate(rot)) => self.rotating = rot,
+            &Event::Rotate(kick) =>
+                self.inputs.push(PieceInput::Rotate(self.rotating, kick)),
+            &Event::Move(vec) if vec == Vector::ONE_DOWN =>
+                self.soft_dropped = true,
+            &Event::Move(vec) => self.inputs.push(PieceInput::Move(vec)),
+            &Event::Place(drop) => {
+                self.pieces += 1;
+                self.drop = drop;
+            },
+            Event::LineClear(clear) => {
+                self.lines += clear.lines;
+                if !self.soft_dropped && !is_finesse_optimal(clear,
+                        count_inputs(clear, self.drop, &self.inputs)) {
+                    self.finesse_faults += 1;
+                }
+                self.reset_inputs();
+            },
+            Event::ZoneClear(lines) => {
+                self.zone_lines += lines;
+                self.max_zone = self.max_zone.max(*lines);
+            },
+            Event::Input(_) | Event::Hint => (),
+        }
+        if let Some(spin_event) = self.spin_handler.handle_spin(event, rules) {
+            if let SpinEvent::LineClear(spin_clear) = &spin_event {
+                let index = spin_clear.clear.active.get_type() as usize;
+                match spin_clear.spin {
+                    Some(SpinType::Full) => self.spins[index] += 1,
+                    Some(SpinType::Mini) => self.mini_spins[index] += 1,
+                    None => (),
+                }
+            }
+            if let Some(chain_event) = self.chain_handler.handle_zone(&spin_event) {
+                self.attack += attack_table.attack(&chain_event);
+                match &chain_event {
+                    ChainEvent::LineClear(chain_clear) if chain_clear.clear.clear.lines > 0 =>
+                        match chain_clear.b2b {
+                            true => self.b2b += 1,
+                            false => self.b2b = 0,
+                        },
+                    ChainEvent::ZoneClear(lines) if *lines > 0 && *lines < 4 =>
+                        self.b2b = 0,
+                    _ => (),
+                }
+            }
+            self.max_b2b = self.max_b2b.max(self.b2b);
+            self.max_combo = self.max_combo
+                .max(self.chain_handler.combo.saturating_sub(1));
+        }
+    }
+
+    fn reset_inputs(&mut self) {
+        self.inputs.clear();
+        self.soft_dropped = false;
+    }
+
+    pub fn total_spins(&self) -> usize {
+        self.spins.iter().sum()
+    }
+
+    pub fn total_mini_spins(&self) -> usize {
+        self.mini_spins.iter().sum()
+    }
+
+    pub fn pps(&self, millis: usize) -> f64 {
+        match millis {
+            0 => 0.0,
+            _ => self.pieces as f64 * 1000.0 / millis as f64,
+        }
+    }
+
+    pub fn apm(&self, millis: usize) -> f64 {
+        match millis {
+            0 => 0.0,
+            _ => self.attack as f64 * 60000.0 / millis as f64,
+        }
+    }
+
+    pub fn to_json(&self, millis: usize) -> String {
+        let by_piece = |counts: &[usize; 7]| EnumSet::<PieceType>::all().iter()
+            .map(|p| format! {"\"{}\":{}", p.serialize(), counts[p as usize]})
+            .collect::<Vec<String>>().join(",");
+        format! {
+            concat!("{{\"time\":{},\"pieces\":{},\"pps\":{:.3},",
+                "\"attack\":{},\"apm\":{:.3},\"lines\":{},",
+                "\"spins\":{{{}}},\"mini_spins\":{{{}}},",
+                "\"max_b2b\":{},\"max_combo\":{},\"finesse_faults\":{},",
+                "\"holds\":{},\"zones\":{},\"zone_lines\":{},\"max_zone\":{}}}"),
+            millis, self.pieces, self.pps(millis),
+            self.attack, self.apm(millis), self.lines,
+            by_piece(&self.spins), by_piece(&self.mini_spins),
+            self.max_b2b, self.max_combo, self.finesse_faults,
+            self.holds, self.zones, self.zone_lines, self.max_zone,
+        }
+    }
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn lands_on(piece: &ActivePiece, board: &Board, target: &[Position; 4]) -> bool {
+    let ghost = piece.get_ghost(board).get_mino_positions();
+    target.iter().all(|p| ghost.contains(p))
+}
+
+// The inputs the piece took, counting every tap but a run of moves that
+// ends against the wall or stack as one auto-shift
+fn count_inputs(clear: &LineClear, drop: usize, inputs: &[PieceInput]) -> usize {
+    let board = &clear.board;
+    // Walk back from where the piece was before it was dropped, noting the
+    // moves that couldn't have gone any further
+    let mut piece = clear.active.clone();
+    piece.pos = piece.pos + Vector::new(0, drop as i32);
+    let mut blocked = vec![false; inputs.len()];
+    for (i, input) in inputs.iter().enumerate().rev() {
+        match *input {
+            PieceInput::Move(vec) => {
+                blocked[i] = !piece.clone().try_move(board, vec);
+                piece.pos = piece.pos - vec;
+            },
+            PieceInput::Rotate(rot, kick) => {
+                piece.rot = piece.rot - rot;
+                piece.pos = piece.pos - piece.piece_type.get_kicks(piece.rot, rot)[kick];
+            },
+        }
+    }
+    let mut count = 0;
+    let mut run = 0;
+    for (i, input) in inputs.iter().enumerate() {
+        match *input {
+            PieceInput::Move(vec) => {
+                let continues = matches!(inputs.get(i + 1),
+                    Some(PieceInput::Move(next)) if *next == vec);
+                run += 1;
+                if !continues {
+                    count += if blocked[i] { 1 } else { run };
+                    run = 0;
+                }
+            },
+            PieceInput::Rotate(_, _) => count += 1,
+        }
+    }
+    count
+}
+
+// Whether the placement couldn't have been reached with fewer inputs,
+// treating taps, auto-shift to the wall and rotations as one input each
+fn is_finesse_optimal(clear: &LineClear, inputs: usize) -> bool {
+    let board = &clear.board;
+    let target = clear.active.get_mino_positions();
+    let start = match ActivePiece::spawn(board, clear.active.get_type(), Rotation::Zero) {
+        Some(piece) => piece,
+        None => return true,
+    };
+    let mut seen = HashSet::from([start.clone()]);
+    let mut frontier = vec![start];
+    for _ in 0..inputs {
+        if frontier.iter().any(|p| lands_on(p, board, &target)) {
+            return false;
+        }
+        let mut next = Vec::new();
+        for piece in frontier {
+            let mut moves = Vec::new();
+            for vec in [Vector::ONE_LEFT, Vector::ONE_RIGHT] {
+                let mut tap = piece.clone();
+                if tap.try_move(board, vec) {
+                    moves.push(tap.clone());
+                    while tap.try_move(board, vec) {
+                        // auto-shifting to the wall
+                    }
+                    moves.push(tap);
+                }
+            }
+            for rot in [Rotation::Clockwise, Rotation::Anticlockwise, Rotation::Flip] {
+                let mut rotated = piece.clone();
+                if rotated.try_rotate(board, rot).is_some() {
+                    moves.push(rotated);
+                }
+            }
+            for piece in moves {
+                if seen.insert(piece.clone()) {
+                    next.push(piece);
+                }
+            }
+        }
+        frontier = next;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Counts the inputs for rotating and moving a T from spawn, then hard
+    // dropping it
+    fn inputs_for(rot: Option<Rotation>, moves: &[Vector]) -> usize {
+        let board = Board::new();
+        let mut piece = ActivePiece::spawn(&board, PieceType::T, Rotation::Zero)
+            .expect("The T spawns on an empty board");
+        let mut inputs = Vec::new();
+        if let Some(rot) = rot {
+            let kick = piece.try_rotate(&board, rot).expect("The T can rotate");
+            inputs.push(PieceInput::Rotate(rot, kick));
+        }
+        inputs.extend(moves.iter()
+            .filter(|vec| piece.try_move(&board, **vec))
+            .map(|vec| PieceInput::Move(*vec)));
+        let ghost = piece.get_ghost(&board);
+        let drop = (piece.pos.y - ghost.pos.y) as usize;
+        let clear = LineClear { lines: 0, active: ghost, board, in_zone: false };
+        count_inputs(&clear, drop, &inputs)
+    }
+
+    #[test]
+    fn taps_count_each() {
+        assert_eq!(inputs_for(None, &[Vector::ONE_LEFT; 2]), 2);
+    }
+
+    #[test]
+    fn auto_shift_counts_once() {
+        assert_eq!(inputs_for(None, &[Vector::ONE_LEFT; 10]), 1);
+        assert_eq!(inputs_for(Some(Rotation::Clockwise), &[Vector::ONE_LEFT; 10]), 2);
+        assert_eq!(inputs_for(None, &[Vector::ONE_RIGHT, Vector::ONE_LEFT, Vector::ONE_LEFT,
+            Vector::ONE_LEFT, Vector::ONE_LEFT, Vector::ONE_LEFT]), 2);
+    }
+}
@@ -0,0 +1,353 @@
+use std::collections::VecDeque;
+
+use crate::condition::board_state::BoardCondition;
+use crate::condition::board_state::BoardGoal;
+use crate::condition::chain::ChainConditions;
+use crate::condition::chain::ChainScorer;
+use crate::condition::event::Conditions;
+use crate::condition::event::EventConditions;
+use crate::condition::event::EventScorer;
+use crate::condition::sequence::Sequence;
+use crate::condition::spin::SpinConditions;
+use crate::condition::spin::SpinScorer;
+use crate::game::ActivePiece;
+use crate::game::BagRandomizer;
+use crate::game::Board;
+use crate::game::BoardMask;
+use crate::game::Game;
+use crate::game::MaybeActive;
+use crate::game::Mino;
+use crate::game::PieceType;
+use crate::game::Queue;
+use crate::metadata::Metadata;
+use crate::position::Position;
+use crate::position::Rotation;
+use crate::position::Vector;
+use crate::puzzle::Puzzle;
+
+// Reflects left to right, swapping S with Z and J with L
+pub trait Mirror {
+    fn mirror(&self) -> Self;
+}
+
+impl<T: Mirror> Mirror for Option<T> {
+    fn mirror(&self) -> Self {
+        self.as_ref().map(|t| t.mirror())
+    }
+}
+
+impl<T: Mirror> Mirror for Vec<T> {
+    fn mirror(&self) -> Self {
+        self.iter().map(|t| t.mirror()).collect()
+    }
+}
+
+impl<T: Mirror> Mirror for Box<T> {
+    fn mirror(&self) -> Self {
+        Box::new(self.as_ref().mirror())
+    }
+}
+
+impl Mirror for PieceType {
+    fn mirror(&self) -> Self {
+        match self {
+            PieceType::S => PieceType::Z,
+            PieceType::Z => PieceType::S,
+            PieceType::J => PieceType::L,
+            PieceType::L => PieceType::J,
+            piece => *piece,
+        }
+    }
+}
+
+impl Mirror for Mino {
+    fn mirror(&self) -> Self {
+        match self {
+            Mino::Piece(piece) => Mino::Piece(piece.mirror()),
+            Mino::Gray => Mino::Gray,
+        }
+    }
+}
+
+impl Mirror for Board {
+    fn mirror(&self) -> Self {
+        Board {
+            matrix: self.matrix.map(|mut row| {
+                row.reverse();
+                row.map(|m| m.mirror())
+            }),
+        }
+    }
+}
+
+impl Mirror for BoardMask {
+    fn mirror(&self) -> Self {
+        BoardMask {
+            matrix: self.matrix.map(|mut row| {
+                row.reverse();
+                row
+            }),
+        }
+    }
+}
+
+fn sorted_positions(mut positions: [Position; 4]) -> [Position; 4] {
+    positions.sort_by_key(|p| (p.x, p.y));
+    positions
+}
+
+impl Mirror for ActivePiece {
+    // Finds the orientation of the mirrored piece covering the reflected
+    // cells, preferring the reflected rotation when several fit
+    fn mirror(&self) -> Self {
+        let piece_type = self.piece_type.mirror();
+        let target = sorted_positions(self.get_mino_positions()
+            .map(|p| Position::new(9 - p.x, p.y)));
+        let rots = [-self.rot, self.rot, self.rot + Rotation::Flip,
+            -self.rot + Rotation::Flip];
+        for rot in rots {
+            let cells = sorted_positions(piece_type.get_mino_vecs()
+                .map(|v| Position::ORIGIN + v.rotate(rot)));
+            let pos = Position::new(target[0].x - cells[0].x,
+                target[0].y - cells[0].y);
+            let piece = ActivePiece { piece_type, pos, rot };
+            if sorted_positions(piece.get_mino_positions()) == target {
+                return piece;
+            }
+        }
+        unreachable!("every piece has a mirrored orientation")
+    }
+}
+
+impl Mirror for MaybeActive {
+    fn mirror(&self) -> Self {
+        match self {
+            MaybeActive::Active(piece) => MaybeActive::Active(piece.mirror()),
+            MaybeActive::Inactive(piece) => MaybeActive::Inactive(piece.mirror()),
+        }
+    }
+}
+
+impl Mirror for Queue {
+    fn mirror(&self) -> Self {
+        Queue {
+            length: self.length,
+            pieces: self.pieces.iter().map(|p| p.mirror())
+                .collect::<VecDeque<PieceType>>(),
+            rando: BagRandomizer {
+                set: self.rando.set.iter().map(|p| p.mirror()).collect(),
+            },
+        }
+    }
+}
+
+impl Mirror for Game {
+    fn mirror(&self) -> Self {
+        Game {
+            piece: self.piece.mirror(),
+            queue: self.queue.mirror(),
+            hold: self.hold.mirror(),
+            board: self.board.mirror(),
+            ..self.clone()
+        }
+    }
+}
+
+impl Mirror for BoardGoal {
+    fn mirror(&self) -> Self {
+        match self {
+            Self::MatchBoard(target) => Self::MatchBoard(target.mirror()),
+            Self::FillCells(target) => Self::FillCells(target.mirror()),
+            Self::Residue(target) => Self::Residue(target.mirror()),
+            goal => goal.clone(),
+        }
+    }
+}
+
+impl Mirror for BoardCondition {
+    fn mirror(&self) -> Self {
+        BoardCondition {
+            goal: self.goal.mirror(),
+            ..self.clone()
+        }
+    }
+}
+
+impl<S: Mirror + Clone> Mirror for Sequence<S> {
+    fn mirror(&self) -> Self {
+        Sequence {
+            steps: self.steps.mirror(),
+            ..self.clone()
+        }
+    }
+}
+
+impl Mirror for ChainScorer {
+    fn mirror(&self) -> Self {
+        let mut scorer = self.clone();
+        if let Self::LineClear { req_piece, .. } = &mut scorer {
+            *req_piece = req_piece.mirror();
+        }
+        scorer
+    }
+}
+
+impl Mirror for SpinScorer {
+    fn mirror(&self) -> Self {
+        let mut scorer = self.clone();
+        if let Self::LineClear { req_piece, .. } = &mut scorer {
+            *req_piece = req_piece.mirror();
+        }
+        scorer
+    }
+}
+
+impl Mirror for EventScorer {
+    fn mirror(&self) -> Self {
+        let mut scorer = self.clone();
+        if let Self::LineClear { req_piece, .. } = &mut scorer {
+            *req_piece = req_piece.mirror();
+        }
+        scorer
+    }
+}
+
+impl Mirror for ChainConditions {
+    fn mirror(&self) -> Self {
+        match self {
+            Self::Condition(target, scorer) =>
+                Self::Condition(target.clone(), scorer.mirror()),
+            Self::Sequence(sequence) => Self::Sequence(sequence.mirror()),
+        }
+    }
+}
+
+impl Mirror for SpinConditions {
+    fn mirror(&self) -> Self {
+        match self {
+            Self::ChainContext(handler, conditions) =>
+                Self::ChainContext(handler.clone(), conditions.mirror()),
+            Self::ZoneChainContext(handler, conditions) =>
+                Self::ZoneChainContext(handler.clone(), conditions.mirror()),
+            Self::Condition(target, scorer) =>
+                Self::Condition(target.clone(), scorer.mirror()),
+            Self::Sequence(sequence) => Self::Sequence(sequence.mirror()),
+        }
+    }
+}
+
+impl Mirror for EventConditions {
+    fn mirror(&self) -> Self {
+        match self {
+            Self::TSpinContext(handler, conditions) =>
+                Self::TSpinContext(handler.clone(), conditions.mirror()),
+            Self::AllSpinContext(handler, conditions) =>
+                Self::AllSpinContext(handler.clone(), conditions.mirror()),
+            Self::RulesSpinContext(handler, conditions) =>
+                Self::RulesSpinContext(handler.clone(), conditions.mirror()),
+            Self::Condition(target, scorer) =>
+                Self::Condition(target.clone(), scorer.mirror()),
+            Self::All(conditions) => Self::All(conditions.mirror()),
+            Self::Any(conditions) => Self::Any(conditions.mirror()),
+            Self::Not(condition) => Self::Not(condition.mirror()),
+            Self::AtLeast(count, conditions) =>
+                Self::AtLeast(*count, conditions.mirror()),
+            Self::Sequence(sequence) => Self::Sequence(sequence.mirror()),
+            Self::Board(condition) => Self::Board(condition.mirror()),
+        }
+    }
+}
+
+impl Mirror for Conditions {
+    fn mirror(&self) -> Self {
+        Conditions {
+            conditions: self.conditions.mirror(),
+        }
+    }
+}
+
+impl Mirror for Metadata {
+    fn mirror(&self) -> Self {
+        Metadata {
+            hints: self.hints.mirror(),
+            ..self.clone()
+        }
+    }
+}
+
+impl Mirror for Puzzle {
+    fn mirror(&self) -> Self {
+        Puzzle {
+            game: self.game.mirror(),
+            win_conditions: self.win_conditions.mirror(),
+            end_conditions: self.end_conditions.mirror(),
+            metadata: self.metadata.mirror(),
+            ..self.clone()
+        }
+    }
+}
+
+fn masks_mut(puzzle: &mut Puzzle) -> Vec<&mut BoardMask> {
+    let mut masks = puzzle.win_conditions.get_masks_mut();
+    masks.extend(puzzle.end_conditions.get_masks_mut());
+    masks
+}
+
+// Moves the field along with any target masks, the active piece and hints.
+// Nothing moves if the active piece would end up in blocks or off the
+// board, or a hint would be pushed off the board.
+pub fn shift(puzzle: &mut Puzzle, vec: Vector) -> bool {
+    let mut board = puzzle.game.board.clone();
+    board.shift(vec);
+    let piece_fits = match &puzzle.game.piece {
+        Some(MaybeActive::Active(piece)) => {
+            let mut piece = piece.clone();
+            piece.pos = piece.pos + vec;
+            !piece.is_colliding(&board)
+        }
+        _ => true,
+    };
+    let hints_fit = puzzle.metadata.hints.iter().all(|hint|
+        hint.get_mino_positions().iter()
+            .all(|p| Board::in_bounds(*p + vec)));
+    if !piece_fits || !hints_fit {
+        return false;
+    }
+    puzzle.game.board = board;
+    for mask in masks_mut(puzzle) {
+        mask.shift(vec);
+    }
+    if let Some(MaybeActive::Active(piece)) = &mut puzzle.game.piece {
+        piece.pos = piece.pos + vec;
+    }
+    for hint in puzzle.metadata.hints.iter_mut() {
+        hint.pos = hint.pos + vec;
+    }
+    true
+}
+
+pub fn insert_row(puzzle: &mut Puzzle, y: usize, row: [Option<Mino>; 10]) {
+    puzzle.game.board.insert_row(y, row);
+    for mask in masks_mut(puzzle) {
+        mask.insert_row(y, [false; 10]);
+    }
+}
+
+pub fn delete_row(puzzle: &mut Puzzle, y: usize) {
+    puzzle.game.board.delete_row(y);
+    for mask in masks_mut(puzzle) {
+        mask.delete_row(y);
+    }
+}
+
+// Moves a column of garbage along with the matching target cells
+pub fn transpose_garbage(puzzle: &mut Puzzle, from: usize, to: usize) {
+    let rows = (0..26).filter(|y| puzzle.game.board.is_garbage_row(*y))
+        .collect::<Vec<usize>>();
+    puzzle.game.board.transpose_garbage(from, to);
+    for mask in masks_mut(puzzle) {
+        for y in rows.iter() {
+            mask.matrix[*y].swap(from, to);
+        }
+    }
+}
@@ -9,12 +9,15 @@ use crate::condition::event::ReqOrMin;
 use crate::condition::event::ScoreTarget;
 use crate::condition::spin::SpinConditions;
 use crate::condition::spin::SpinHandler;
+use crate::condition::spin::SpinRules;
 use crate::condition::spin::SpinScorer;
 use crate::game::BagRandomizer;
 use crate::game::Game;
 use crate::game::PieceType;
 use crate::game::Queue;
+use crate::metadata::Metadata;
 use crate::puzzle::Puzzle;
+use crate::scoring::Scoring;
 
 const NUM_BAGS: usize = 25;
 
@@ -45,7 +48,7 @@ pub fn generate() -> Puzzle {
         game,
         win_conditions: Conditions {
             conditions: vec![
-                EventConditions::TSpinContext(
+                EventConditions::RulesSpinContext(
                     SpinHandler::new(None),
                     vec![
                         SpinConditions::Condition(
@@ -65,7 +68,7 @@ pub fn generate() -> Puzzle {
         },
         end_conditions: Conditions {
             conditions: vec![
-                EventConditions::TSpinContext(
+                EventConditions::RulesSpinContext(
                     SpinHandler::new(None),
                     vec![
                         SpinConditions::Condition(
@@ -85,5 +88,8 @@ pub fn generate() -> Puzzle {
         },
         won: false,
         over: false,
+        scoring: Scoring::default(),
+        spin_rules: SpinRules::default(),
+        metadata: Metadata::default(),
     }
 }
\ No newline at end of file
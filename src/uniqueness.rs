@@ -0,0 +1,51 @@
+use std::collections::HashSet;
+
+use crate::game::Board;
+use crate::position::Position;
+use crate::puzzle::Puzzle;
+use crate::solver::Solution;
+use crate::solver::placement_set;
+use crate::solver::Solver;
+
+// Enough to show an author what else works without a long wait
+pub const DEFAULT_MAX_SOLUTIONS: usize = 8;
+
+// Solutions placing the same pieces in the same spots and leaving the same
+// board are one solution, whatever order or inputs they used
+pub fn solution_key(solution: &Solution) -> (Board, Vec<[Position; 4]>) {
+    (solution.puzzle.game.board.clone(), placement_set(&solution.moves))
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub struct SolutionCheck {
+    // Distinct solutions, the one the solver likes best first
+    pub solutions: Vec<Solution>,
+    // Whether the search covered every line, so no solution was missed
+    pub complete: bool,
+}
+
+impl SolutionCheck {
+    pub fn is_unique(&self) -> bool {
+        self.complete && self.solutions.len() == 1
+    }
+
+    pub fn alternatives(&self) -> &[Solution] {
+        self.solutions.get(1..).unwrap_or(&[])
+    }
+}
+
+// Finds up to max_solutions distinct solutions
+pub fn check(puzzle: &Puzzle, max_solutions: usize) -> SolutionCheck {
+    let mut seen = HashSet::new();
+    let report = Solver::default().distinct_solutions(puzzle, max_solutions,
+        |solution| seen.insert(solution_key(solution)));
+    SolutionCheck {
+        solutions: report.solutions,
+        complete: report.complete,
+    }
+}
+
+// Whether a second distinct solution turns up, for generators to reject
+pub fn is_ambiguous(puzzle: &Puzzle) -> bool {
+    check(puzzle, 2).solutions.len() > 1
+}
@@ -0,0 +1,321 @@
+use std::fmt;
+
+use crate::condition::chain::ChainConditions;
+use crate::condition::chain::ChainScorer;
+use crate::condition::event::EventConditions;
+use crate::condition::event::EventScorer;
+use crate::condition::event::ScoreTarget;
+use crate::condition::spin::SpinConditions;
+use crate::condition::spin::SpinScorer;
+use crate::game::ActivePiece;
+use crate::game::Game;
+use crate::game::MaybeActive;
+use crate::game::Mino;
+use crate::game::PieceType;
+use crate::position::Rotation;
+use crate::puzzle::Puzzle;
+use crate::serialize::SerializeUrlSafe;
+
+use enumset::EnumSet;
+
+#[derive(Hash, Eq, PartialEq, Copy, Clone)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Hash, Eq, PartialEq, Clone)]
+pub enum Issue {
+    NoWinConditions,
+    NotEnoughPieces {
+        needed: usize,
+        available: usize,
+    },
+    QueueTooShort {
+        needed: usize,
+        shown: usize,
+    },
+    HoldConflictsWithBag(PieceType),
+    QueueConflictsWithBag(PieceType),
+    ActivePieceColliding,
+    SpawnBlocked(PieceType),
+    FloatingFullRows(usize),
+    AlreadyOver,
+    EndsBeforeWin,
+}
+
+impl Issue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::NoWinConditions => Severity::Warning,
+            Self::NotEnoughPieces {..} => Severity::Error,
+            Self::QueueTooShort {..} => Severity::Warning,
+            Self::HoldConflictsWithBag(_) => Severity::Warning,
+            Self::QueueConflictsWithBag(_) => Severity::Warning,
+            Self::ActivePieceColliding => Severity::Error,
+            Self::SpawnBlocked(_) => Severity::Error,
+            Self::FloatingFullRows(_) => Severity::Warning,
+            Self::AlreadyOver => Severity::Error,
+            Self::EndsBeforeWin => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoWinConditions =>
+                write!(f, "There are no win conditions, so the puzzle can't be won."),
+            Self::NotEnoughPieces { needed, available } =>
+                write!(f, "The win conditions need at least {} pieces, but the puzzle ends after {}.",
+                    needed, available),
+            Self::QueueTooShort { needed, shown } =>
+                write!(f, "The win conditions need at least {} pieces, but only {} are shown, so the rest will be random.",
+                    needed, shown),
+            Self::HoldConflictsWithBag(piece) =>
+                write!(f, "The hold piece {} is still left in the bag.", piece.serialize()),
+            Self::QueueConflictsWithBag(piece) =>
+                write!(f, "The {} piece appears twice in the same bag.", piece.serialize()),
+            Self::ActivePieceColliding =>
+                write!(f, "The active piece overlaps blocks on the board."),
+            Self::SpawnBlocked(piece) =>
+                write!(f, "The {} piece has no room to spawn.", piece.serialize()),
+            Self::FloatingFullRows(rows) =>
+                write!(f, "The board has {} full rows that haven't been cleared.", rows),
+            Self::AlreadyOver =>
+                write!(f, "The end conditions are already met, so the puzzle ends immediately."),
+            Self::EndsBeforeWin =>
+                write!(f, "An end condition is always reached before a matching win condition."),
+        }
+    }
+}
+
+fn remaining(target: &ScoreTarget) -> usize {
+    target.target.saturating_sub(target.score)
+}
+
+// Lower bounds on the pieces that must be placed before a condition is met
+
+fn chain_pieces(conditions: &ChainConditions) -> usize {
+    let scorer_pieces = |scorer: &ChainScorer, target: usize| match scorer {
+        ChainScorer::LineClear {..} => target,
+        ChainScorer::LinesCleared => target.div_ceil(4),
+        _ => 0,
+    };
+    match conditions {
+        ChainConditions::Condition(target, scorer) =>
+            scorer_pieces(scorer, remaining(target)),
+        ChainConditions::Sequence(sequence) =>
+            sequence.steps[sequence.progress.min(sequence.steps.len())..]
+                .iter().map(|s| scorer_pieces(s, 1)).sum(),
+    }
+}
+
+fn spin_pieces(conditions: &SpinConditions) -> usize {
+    let scorer_pieces = |scorer: &SpinScorer, target: usize| match scorer {
+        SpinScorer::LineClear {..} => target,
+        SpinScorer::LinesCleared => target.div_ceil(4),
+        SpinScorer::ZoneClear {..} => 0,
+    };
+    match conditions {
+        SpinConditions::ChainContext(_, conditions)
+            | SpinConditions::ZoneChainContext(_, conditions) =>
+            conditions.iter().map(chain_pieces).max().unwrap_or(0),
+        SpinConditions::Condition(target, scorer) =>
+            scorer_pieces(scorer, remaining(target)),
+        SpinConditions::Sequence(sequence) =>
+            sequence.steps[sequence.progress.min(sequence.steps.len())..]
+                .iter().map(|s| scorer_pieces(s, 1)).sum(),
+    }
+}
+
+fn event_pieces(conditions: &EventConditions) -> usize {
+    let scorer_pieces = |scorer: &EventScorer, target: usize| match scorer {
+        EventScorer::LineClear {..} => target,
+        EventScorer::LinesCleared => target.div_ceil(4),
+        EventScorer::PiecesPlaced => target,
+        _ => 0,
+    };
+    match conditions {
+        EventConditions::TSpinContext(_, conditions)
+            | EventConditions::AllSpinContext(_, conditions)
+            | EventConditions::RulesSpinContext(_, conditions) =>
+            conditions.iter().map(spin_pieces).max().unwrap_or(0),
+        EventConditions::Condition(target, scorer) =>
+            scorer_pieces(scorer, remaining(target)),
+        EventConditions::All(conditions) =>
+            conditions.iter().map(event_pieces).max().unwrap_or(0),
+        EventConditions::Any(conditions) =>
+            conditions.iter().map(event_pieces).min().unwrap_or(0),
+        EventConditions::AtLeast(count, conditions) => {
+            let mut pieces: Vec<usize> = conditions.iter()
+                .map(event_pieces).collect();
+            pieces.sort();
+            match *count {
+                0 => 0,
+                n => pieces.get(n - 1).copied().unwrap_or(0),
+            }
+        }
+        EventConditions::Not(_) => 0,
+        EventConditions::Sequence(sequence) =>
+            sequence.steps[sequence.progress.min(sequence.steps.len())..]
+                .iter().map(|s| scorer_pieces(s, 1)).sum(),
+        EventConditions::Board(_) => 0,
+    }
+}
+
+// Upper bounds on the pieces that can be placed before a condition is met,
+// if it's certain to be met by then
+
+fn end_pieces(conditions: &EventConditions) -> Option<usize> {
+    // Every piece takes at least one input to place
+    let scorer_pieces = |scorer: &EventScorer, target: usize| match scorer {
+        EventScorer::PiecesPlaced | EventScorer::Inputs { .. } => Some(target),
+        _ => None,
+    };
+    match conditions {
+        EventConditions::Condition(target, scorer) =>
+            scorer_pieces(scorer, remaining(target)),
+        EventConditions::All(conditions) => conditions.iter()
+            .map(end_pieces).collect::<Option<Vec<usize>>>()?
+            .into_iter().max(),
+        EventConditions::Any(conditions) =>
+            conditions.iter().filter_map(end_pieces).min(),
+        EventConditions::AtLeast(count, conditions) => {
+            let mut pieces: Vec<usize> = conditions.iter()
+                .filter_map(end_pieces).collect();
+            pieces.sort();
+            match *count {
+                0 => Some(0),
+                n => pieces.get(n - 1).copied(),
+            }
+        }
+        EventConditions::Sequence(sequence) =>
+            sequence.steps[sequence.progress.min(sequence.steps.len())..]
+                .iter().map(|s| scorer_pieces(s, 1)).sum(),
+        _ => None,
+    }
+}
+
+fn check_pieces(puzzle: &Puzzle, issues: &mut Vec<Issue>) {
+    let needed = puzzle.win_conditions.conditions.iter()
+        .map(event_pieces).max().unwrap_or(0);
+    let available = puzzle.end_conditions.conditions.iter()
+        .filter_map(end_pieces).min();
+    match available {
+        Some(available) if needed > available =>
+            issues.push(Issue::NotEnoughPieces { needed, available }),
+        _ => (),
+    }
+    // Pieces past the end of the queue come from the bag
+    let game = &puzzle.game;
+    let shown = game.hold.iter().count() + game.get_current().iter().count()
+        + game.queue.pieces.len();
+    if needed > shown {
+        issues.push(Issue::QueueTooShort { needed, shown });
+    }
+}
+
+fn check_bag(game: &Game, issues: &mut Vec<Issue>) {
+    // Pieces in the order they were drawn, with the hold drawn first
+    let drawn: Vec<(PieceType, bool)> = game.hold.iter().map(|p| (*p, true))
+        .chain(game.get_current().map(|p| (p, false)))
+        .chain(game.queue.pieces.iter().map(|p| (*p, false)))
+        .collect();
+    // The randomizer keeps what's left in the bag, so the latest draws are
+    // the rest of it. Walking back through them takes each out of the bag.
+    let mut bag = EnumSet::all() - game.queue.rando.set;
+    let mut reported: EnumSet<PieceType> = EnumSet::new();
+    for (piece, is_hold) in drawn.into_iter().rev() {
+        // Once it's empty, earlier draws came from the full bag before it
+        if bag.is_empty() {
+            bag = EnumSet::all();
+        }
+        if bag.contains(piece) {
+            bag -= piece;
+        } else if reported.insert(piece) {
+            issues.push(match is_hold {
+                true => Issue::HoldConflictsWithBag(piece),
+                false => Issue::QueueConflictsWithBag(piece),
+            });
+        }
+    }
+}
+
+fn check_piece(game: &Game, issues: &mut Vec<Issue>) {
+    match &game.piece {
+        Some(MaybeActive::Active(piece))
+                if piece.is_colliding(&game.board) =>
+            issues.push(Issue::ActivePieceColliding),
+        // In zone a blocked spawn ends the zone first, clearing its lines
+        Some(MaybeActive::Inactive(piece))
+                if !game.in_zone && ActivePiece::spawn(&game.board, *piece,
+                    Rotation::Zero).is_none() =>
+            issues.push(Issue::SpawnBlocked(*piece)),
+        _ => (),
+    }
+}
+
+fn check_rows(game: &Game, issues: &mut Vec<Issue>) {
+    let full = |row: &[Option<Mino>; 10]| row.iter().all(|m| m.is_some());
+    // Zone lines are stacked at the bottom and only cleared when it ends
+    let zone_lines = match game.in_zone {
+        true => game.board.matrix.iter()
+            .take_while(|row| **row == [Some(Mino::Gray); 10]).count(),
+        false => 0,
+    };
+    let rows = game.board.matrix[zone_lines..].iter()
+        .filter(|row| full(row)).count();
+    if rows > 0 {
+        issues.push(Issue::FloatingFullRows(rows));
+    }
+}
+
+fn check_conditions(puzzle: &Puzzle, issues: &mut Vec<Issue>) {
+    let win_conditions = &puzzle.win_conditions.conditions;
+    if win_conditions.is_empty() {
+        issues.push(Issue::NoWinConditions);
+    }
+    if puzzle.end_conditions.statuses().iter().any(|s| *s)
+        || puzzle.win_conditions.failed() {
+        issues.push(Issue::AlreadyOver);
+    }
+    let ends_before_win = win_conditions.iter().any(|win| puzzle.end_conditions
+        .conditions.iter().any(|end| match (win, end) {
+            (EventConditions::Condition(win_target, win_scorer),
+                EventConditions::Condition(end_target, end_scorer)) =>
+                win_scorer == end_scorer
+                    && remaining(end_target) < remaining(win_target),
+            _ => false,
+        }));
+    if ends_before_win {
+        issues.push(Issue::EndsBeforeWin);
+    }
+}
+
+pub fn validate(puzzle: &Puzzle) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    check_conditions(puzzle, &mut issues);
+    check_pieces(puzzle, &mut issues);
+    check_piece(&puzzle.game, &mut issues);
+    check_bag(&puzzle.game, &mut issues);
+    check_rows(&puzzle.game, &mut issues);
+    issues.sort_by_key(|i| i.severity() == Severity::Warning);
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kirb_generator;
+    use crate::pc_generator;
+    use crate::pc_generator::PcSettings;
+
+    #[test]
+    fn generated_puzzles_have_no_issues() {
+        let puzzle = kirb_generator::generate(0);
+        assert!(validate(&puzzle).is_empty());
+        let puzzle = pc_generator::generate(&PcSettings::new(pc_generator::MIN_HEIGHT));
+        assert!(validate(&puzzle).is_empty());
+    }
+}
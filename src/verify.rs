@@ -0,0 +1,136 @@
+use std::fmt;
+
+use crate::game::Action;
+use crate::game::MaybeActive;
+use crate::game::game::Event;
+use crate::puzzle::Puzzle;
+use crate::replay::Info;
+use crate::replay::TimedLog;
+use crate::serialize::SerializeUrlSafe;
+
+pub struct Verification {
+    pub pieces: usize,
+    // Milliseconds from the first action to the last
+    pub time: usize,
+}
+
+pub enum VerifyError {
+    Malformed(String),
+    WrongPuzzle,
+    MissingInit,
+    ImpossibleAction(usize),
+    TimeWentBackwards(usize),
+    MissingRandomness,
+    NotWon,
+    Over,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(msg) => write!(f, "The replay couldn't be read: {}", msg),
+            Self::WrongPuzzle => write!(f, "The replay was recorded on a different puzzle."),
+            Self::MissingInit => write!(f, "The replay doesn't start by initializing the puzzle."),
+            Self::ImpossibleAction(i) => write!(f, "Action #{} isn't possible at that point.", i + 1),
+            Self::TimeWentBackwards(i) => write!(f, "Action #{} happened before the one preceding it.", i + 1),
+            Self::MissingRandomness => write!(f, "The replay needs more random values than it recorded."),
+            Self::NotWon => write!(f, "The replay doesn't win the puzzle."),
+            Self::Over => write!(f, "The puzzle ended before the replay won it."),
+        }
+    }
+}
+
+// Whether an honest client could have sent the action in this state
+fn is_possible(puzzle: &Puzzle, action: &Action) -> bool {
+    let game = &puzzle.game;
+    match action {
+        Action::Init => false,
+        Action::SpawnPiece(_, _) =>
+            matches!(game.piece, Some(MaybeActive::Inactive(_))),
+        Action::PlacePiece =>
+            matches!(game.piece, Some(MaybeActive::Active(_))),
+        _ => true,
+    }
+}
+
+pub fn verify(puzzle: &Puzzle, replay: &str) -> Result<Verification, VerifyError> {
+    let log = TimedLog::deserialize_string(replay)
+        .map_err(|e| VerifyError::Malformed(e.to_string()))?;
+    if log.puzzle != *puzzle {
+        return Err(VerifyError::WrongPuzzle);
+    }
+    let recorded = log.info.len();
+    let mut info = Info::from_values(log.info);
+    let mut puzzle = puzzle.clone();
+    let mut pieces = 0;
+    let start = match log.actions.first() {
+        Some(action) if matches!(action.action, Action::Init) => action.time,
+        _ => return Err(VerifyError::MissingInit),
+    };
+    let mut prev = start;
+    for (i, action) in log.actions.iter().enumerate() {
+        if action.time < prev {
+            return Err(VerifyError::TimeWentBackwards(i));
+        }
+        if i > 0 && (puzzle.over || !is_possible(&puzzle, &action.action)) {
+            return Err(VerifyError::ImpossibleAction(i));
+        }
+        puzzle.update(action.action, &mut info, &mut |event| {
+            if let Event::Place(_) = event {
+                pieces += 1;
+            }
+        });
+        if info.get_index() > recorded {
+            return Err(VerifyError::MissingRandomness);
+        }
+        prev = action.time;
+        // Winning often ends the puzzle too, so anything after is ignored
+        if puzzle.won {
+            break;
+        }
+    }
+    if !puzzle.won {
+        return Err(match puzzle.over {
+            true => VerifyError::Over,
+            false => VerifyError::NotWon,
+        });
+    }
+    Ok(Verification {
+        pieces,
+        time: prev - start,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kirb_generator;
+    use crate::pc_generator;
+    use crate::pc_generator::PcSettings;
+    use crate::solver::Solver;
+
+    // Solves the puzzle and verifies the solver's own replay of it
+    fn verify_solved(puzzle: &Puzzle) -> Option<Result<Verification, VerifyError>> {
+        let solution = Solver::default().solutions(puzzle, 1)
+            .solutions.into_iter().next()?;
+        let log = solution.replay(puzzle).get_log().to_string();
+        Some(verify(puzzle, &log))
+    }
+
+    #[test]
+    fn kirb_solution_verifies() {
+        // Not every generated kirb is solvable within the solver's limits
+        let result = (0..10)
+            .find_map(|_| verify_solved(&kirb_generator::generate(0)))
+            .expect("the solver should solve one of the generated kirbs");
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn perfect_clear_solution_verifies() {
+        let puzzle = pc_generator::generate(&PcSettings::new(pc_generator::MIN_HEIGHT));
+        let result = verify_solved(&puzzle)
+            .expect("the solver should solve the generated perfect clear");
+        assert!(result.is_ok());
+    }
+}